@@ -11,7 +11,7 @@ fn benchmark_process_data_py(c: &mut Criterion) {
 
     Python::with_gil(|py| {
         // 导入 partial_json_parser 模块
-        let partial_json_parser = PyModule::import_bound(py, "partial_json_parser").unwrap();
+        let partial_json_parser = PyModule::import(py, "partial_json_parser").unwrap();
 
         let test_cases = test_utils::gen_test_cases(100);
 
@@ -0,0 +1,146 @@
+use std::fmt;
+use std::ops::{BitAnd, BitOr, BitOrAssign, Not, Sub};
+
+/// 控制哪些类型的值允许以残缺的形式出现在补全结果中，
+/// 与 python 版本 `partial_json_parser` 的 `Allow` 保持一致。
+///
+/// 完整的值总是会被保留，这里的标志只影响尚未结束的值。
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Allow(u16);
+
+impl Allow {
+    pub const STR: Allow = Allow(0x001);
+    pub const NUM: Allow = Allow(0x002);
+    pub const ARR: Allow = Allow(0x004);
+    pub const OBJ: Allow = Allow(0x008);
+    pub const NULL: Allow = Allow(0x010);
+    pub const BOOL: Allow = Allow(0x020);
+    pub const NAN: Allow = Allow(0x040);
+    pub const INFINITY: Allow = Allow(0x080);
+    pub const NINFINITY: Allow = Allow(0x100);
+
+    pub const INF: Allow = Allow(Self::INFINITY.0 | Self::NINFINITY.0);
+    pub const SPECIAL: Allow = Allow(Self::NULL.0 | Self::BOOL.0 | Self::INF.0 | Self::NAN.0);
+    pub const ATOM: Allow = Allow(Self::STR.0 | Self::NUM.0 | Self::SPECIAL.0);
+    pub const COLLECTION: Allow = Allow(Self::ARR.0 | Self::OBJ.0);
+    pub const ALL: Allow = Allow(Self::ATOM.0 | Self::COLLECTION.0);
+
+    const NAMES: [(&'static str, Allow); 9] = [
+        ("STR", Self::STR),
+        ("NUM", Self::NUM),
+        ("ARR", Self::ARR),
+        ("OBJ", Self::OBJ),
+        ("NULL", Self::NULL),
+        ("BOOL", Self::BOOL),
+        ("NAN", Self::NAN),
+        ("INFINITY", Self::INFINITY),
+        ("NINFINITY", Self::NINFINITY),
+    ];
+
+    pub const fn empty() -> Allow {
+        Allow(0)
+    }
+
+    pub const fn bits(&self) -> u16 {
+        self.0
+    }
+
+    pub const fn from_bits_truncate(bits: u16) -> Allow {
+        Allow(bits & Self::ALL.0)
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn contains(&self, other: Allow) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub const fn intersects(&self, other: Allow) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    pub fn insert(&mut self, other: Allow) {
+        self.0 |= other.0
+    }
+
+    pub fn remove(&mut self, other: Allow) {
+        self.0 &= !other.0
+    }
+}
+
+impl BitOr for Allow {
+    type Output = Allow;
+
+    fn bitor(self, rhs: Allow) -> Allow {
+        Allow(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Allow {
+    fn bitor_assign(&mut self, rhs: Allow) {
+        self.0 |= rhs.0
+    }
+}
+
+impl BitAnd for Allow {
+    type Output = Allow;
+
+    fn bitand(self, rhs: Allow) -> Allow {
+        Allow(self.0 & rhs.0)
+    }
+}
+
+impl Sub for Allow {
+    type Output = Allow;
+
+    fn sub(self, rhs: Allow) -> Allow {
+        Allow(self.0 & !rhs.0)
+    }
+}
+
+impl Not for Allow {
+    type Output = Allow;
+
+    fn not(self) -> Allow {
+        Allow::from_bits_truncate(!self.0)
+    }
+}
+
+impl fmt::Debug for Allow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "Allow(empty)");
+        }
+        let names: Vec<&str> = Self::NAMES
+            .iter()
+            .filter(|(_, flag)| self.contains(*flag))
+            .map(|(name, _)| *name)
+            .collect();
+        write!(f, "Allow({})", names.join(" | "))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Allow;
+
+    #[test]
+    fn test_composite() {
+        assert!(Allow::ALL.contains(Allow::ATOM));
+        assert!(Allow::ALL.contains(Allow::COLLECTION));
+        assert!(Allow::SPECIAL.contains(Allow::INF | Allow::NAN));
+        assert!(!Allow::ATOM.intersects(Allow::COLLECTION));
+        assert_eq!(Allow::ATOM | Allow::COLLECTION, Allow::ALL);
+        assert_eq!(Allow::ALL - Allow::COLLECTION, Allow::ATOM);
+        assert_eq!(!Allow::ATOM, Allow::COLLECTION);
+        assert_eq!(Allow::from_bits_truncate(u16::MAX), Allow::ALL);
+    }
+
+    #[test]
+    fn test_debug() {
+        assert_eq!(format!("{:?}", Allow::empty()), "Allow(empty)");
+        assert_eq!(format!("{:?}", Allow::INF), "Allow(INFINITY | NINFINITY)");
+    }
+}
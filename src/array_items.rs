@@ -0,0 +1,296 @@
+use std::ops::Range;
+
+use serde_json::Value;
+
+use crate::parser::{CharType, ParseSettings, Parser, ScanVisitor};
+use crate::pointer::JsonPath;
+use crate::utils::RunState;
+use crate::value_parser::{is_space, unescape_string};
+use crate::{Dialect, Error};
+
+/// 目标数组中已经结束的一个元素
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArrayItem {
+    /// 在目标数组中的下标
+    pub index: usize,
+    /// 元素在全部输入中的字节范围，不包括两侧的空白，方言输入时同样是原始输入中的范围
+    pub range: Range<usize>,
+    /// 元素的内容，方言输入时是翻译之后的json
+    pub raw: String,
+    // 读取时使用的设置，`raw`已经是翻译之后的json
+    settings: ParseSettings,
+}
+
+impl ArrayItem {
+    /// 按照读取时的设置解析元素，例如其中的`NaN`按照[`ParseSettings::non_finite`]处理
+    pub fn value(&self) -> Result<Value, Error> {
+        crate::loads(&self.raw, self.settings.clone())
+    }
+}
+
+/// 在增量输入中逐个取出数组的元素：数组和对象在右括号、字符串在结束的引号到达时立即产生，
+/// 数字等其它原子值要等到其后的逗号或者数组的右括号到达。
+///
+/// 默认取根节点的数组，也可以用 JSON Pointer 指定嵌套的数组，例如`/results`，
+/// 只有第一个位于该位置的数组会被处理。
+#[derive(Debug)]
+pub struct ArrayItems {
+    parser: Parser<'static>,
+    collector: ItemCollector,
+}
+
+impl Default for ArrayItems {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ArrayItems {
+    pub fn new() -> ArrayItems {
+        Self::with_pointer("")
+    }
+
+    pub fn with_pointer(pointer: &str) -> ArrayItems {
+        Self::with_settings(pointer, ParseSettings::default())
+    }
+
+    pub fn with_settings(pointer: &str, settings: impl Into<ParseSettings>) -> ArrayItems {
+        let settings = settings.into();
+        let mut parser = Parser::parser_owned(settings.clone());
+        parser.parse();
+        ArrayItems {
+            parser,
+            collector: ItemCollector {
+                pointer: pointer.to_string(),
+                settings: settings.for_complete().dialect(Dialect::Json),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// 追加输入，返回由这部分输入结束的元素。
+    ///
+    /// 出错之前已经结束的元素先被返回，错误在下一次调用时报告。
+    pub fn feed(&mut self, chunk: &str) -> Result<Vec<ArrayItem>, Error> {
+        self.parser.push_str_with(chunk, &mut self.collector);
+        let items = std::mem::take(&mut self.collector.items);
+        if items.is_empty() {
+            if let RunState::Error(err) = self.parser.is_parsed() {
+                return Err(self.parser.source_error(err.clone()));
+            }
+        }
+        Ok(items
+            .into_iter()
+            .map(|(mut item, end)| {
+                item.range = self.parser.source_range(item.range, end);
+                item
+            })
+            .collect())
+    }
+
+    /// 目标数组的右括号是否已经到达
+    pub fn is_finished(&self) -> bool {
+        self.collector.finished
+    }
+}
+
+#[derive(Debug, Default)]
+struct ItemCollector {
+    pointer: String,
+    settings: ParseSettings,
+    path: JsonPath,
+    depth: usize,
+    // 上一个结构字符之后的位置
+    last: usize,
+    // 目标数组的深度，以及当前元素的起始位置和下标
+    target: Option<usize>,
+    item_start: usize,
+    index: usize,
+    // 目标数组中的字符串元素是否还没有结束
+    in_string: bool,
+    finished: bool,
+    // 结束的元素，以及它之后的逗号或者右括号的位置
+    items: Vec<(ArrayItem, usize)>,
+}
+
+impl ItemCollector {
+    fn push_item(&mut self, src: &str, end: usize) {
+        let raw = src[self.item_start..end].trim_end_matches(is_space);
+        let item = raw.trim_start_matches(is_space);
+        if item.is_empty() {
+            return;
+        }
+        let start = self.item_start + raw.len() - item.len();
+        let item = ArrayItem {
+            index: self.index,
+            range: start..start + item.len(),
+            raw: item.to_string(),
+            settings: self.settings.clone(),
+        };
+        self.items.push((item, end));
+        self.index += 1;
+    }
+}
+
+impl ScanVisitor for ItemCollector {
+    fn visit(&mut self, src: &str, idx: usize, char_type: CharType) {
+        let key = src[self.last..idx].trim_matches(is_space);
+        self.last = idx + 1;
+        let in_target = self.target == Some(self.depth);
+        match char_type {
+            CharType::LFB | CharType::LCB => {
+                if char_type == CharType::LFB
+                    && self.target.is_none()
+                    && !self.finished
+                    && self.path.pointer() == self.pointer
+                {
+                    self.target = Some(self.depth + 1);
+                    self.item_start = idx + 1;
+                }
+                self.depth += 1;
+                if char_type == CharType::LFB {
+                    self.path.enter_array();
+                } else {
+                    self.path.enter_object();
+                }
+            }
+            CharType::RFB | CharType::RCB => {
+                if in_target {
+                    self.push_item(src, idx);
+                    self.target = None;
+                    self.finished = true;
+                } else if self.target.map(|target| target + 1) == Some(self.depth) {
+                    // 作为元素的数组或对象已经结束，不需要等待后面的逗号
+                    self.push_item(src, idx + 1);
+                    self.item_start = idx + 1;
+                }
+                self.depth = self.depth.saturating_sub(1);
+                self.path.leave();
+            }
+            CharType::Comma => {
+                if in_target {
+                    self.push_item(src, idx);
+                    self.item_start = idx + 1;
+                }
+                self.path.next();
+            }
+            CharType::Colon => self.path.set_key(unescape_string(key)),
+            _ => {}
+        }
+    }
+
+    fn visit_quote(&mut self, src: &str, idx: usize) {
+        if self.target != Some(self.depth) {
+            return;
+        }
+        self.in_string = !self.in_string;
+        if !self.in_string {
+            // 作为元素的字符串已经结束
+            self.push_item(src, idx + 1);
+            self.item_start = idx + 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ArrayItems;
+    use crate::test_utils::{arb_json, normalize};
+    use crate::{Dialect, Error, NonFinitePolicy, ParseSettings};
+    use proptest::prelude::*;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn test_array_items() {
+        let mut items = ArrayItems::new();
+        assert_eq!(items.feed(r#"[{"a": [1, 2]}, "#).unwrap().len(), 1);
+        let res = items.feed(r#""x,y" , 3"#).unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].raw, r#""x,y""#);
+        assert_eq!((res[0].index, res[0].range.clone()), (1, 16..21));
+        let res = items.feed("]").unwrap();
+        assert_eq!(res[0].value().unwrap(), json!(3));
+        assert!(items.is_finished());
+
+        // 数组、对象以及字符串在结束时立即产生
+        let mut items = ArrayItems::new();
+        assert_eq!(items.feed(r#"[{"a":1}"#).unwrap()[0].raw, r#"{"a":1}"#);
+        let res = items.feed(r#", "b\"", [2], 3"#).unwrap();
+        let raws: Vec<&str> = res.iter().map(|item| item.raw.as_str()).collect();
+        assert_eq!(raws, [r#""b\"""#, "[2]"]);
+        assert_eq!((res[1].index, res[1].range.clone()), (2, 17..20));
+
+        // 元素按照读取时的设置解析
+        let settings = ParseSettings::new().non_finite(NonFinitePolicy::Error);
+        let mut items = ArrayItems::with_settings("", settings);
+        let res = items.feed("[NaN, 1]").unwrap();
+        assert_eq!(res[0].value(), Err(Error::NonFinite { offset: 0 }));
+        assert_eq!(res[1].value().unwrap(), json!(1));
+
+        let mut items = ArrayItems::with_pointer("/results");
+        let res = items
+            .feed(r#"{"meta": [0], "results": [{"id": 1}, {"id": 2"#)
+            .unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].value().unwrap(), json!({"id": 1}));
+        let res = items.feed("}], \"results2\": [5]}").unwrap();
+        assert_eq!(res[0].value().unwrap(), json!({"id": 2}));
+        assert_eq!(res.len(), 1);
+
+        // 同一段输入中出错之前结束的元素不会丢失，错误在下一次调用时报告
+        let mut items = ArrayItems::new();
+        let res = items.feed("[1, 2, 3]]").unwrap();
+        let raws: Vec<&str> = res.iter().map(|item| item.raw.as_str()).collect();
+        assert_eq!(raws, ["1", "2", "3"]);
+        assert!(items.is_finished());
+        let err = Error::MismatchedBracket {
+            offset: 9,
+            found: ']',
+            expected: None,
+        };
+        assert_eq!(items.feed(""), Err(err.clone()));
+        assert_eq!(items.feed("4"), Err(err));
+    }
+
+    #[test]
+    fn test_array_items_dialect() {
+        // 方言输入时range是原始输入中的范围，raw是翻译之后的json
+        let src = r#"[1, 'a"b', /* c */ 0x10 , {a: [2]}, +.5 // d
+ ]"#;
+        let json5 = ParseSettings::new().dialect(Dialect::Json5);
+        let mut items = ArrayItems::with_settings("", json5);
+        let mut res = Vec::new();
+        for chunk in src.as_bytes().chunks(3) {
+            res.extend(items.feed(std::str::from_utf8(chunk).unwrap()).unwrap());
+        }
+        let ranges: Vec<&str> = res.iter().map(|item| &src[item.range.clone()]).collect();
+        assert_eq!(ranges, ["1", r#"'a"b'"#, "0x10", "{a: [2]}", "+.5"]);
+        assert_eq!(res[1].raw, r#""a\"b""#);
+        let src = "[True, (1, 'x'), b'y' , None]";
+        let python = ParseSettings::new().dialect(Dialect::PythonLiteral);
+        let mut items = ArrayItems::with_settings("", python);
+        let res = items.feed(src).unwrap();
+        let ranges: Vec<&str> = res.iter().map(|item| &src[item.range.clone()]).collect();
+        assert_eq!(ranges, ["True", "(1, 'x')", "b'y'", "None"]);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(256))]
+        #[test]
+        fn array_items_prop(v in proptest::collection::vec(arb_json(), 0..6), chunk_len in 1usize..8) {
+            let items_str: Vec<String> = v.iter().map(|item| item.to_string()).collect();
+            let s = format!("[{}]", items_str.join(", "));
+            let mut items = ArrayItems::new();
+            let chars: Vec<char> = s.chars().collect();
+            let mut res = Vec::new();
+            for chunk in chars.chunks(chunk_len) {
+                let chunk: String = chunk.iter().collect();
+                res.extend(items.feed(&chunk).unwrap());
+            }
+            let expected = json5::from_str::<Value>(&s).unwrap();
+            let values: Vec<Value> = res.iter().map(|item| item.value().unwrap()).collect();
+            prop_assert_eq!(normalize(Value::Array(values)), normalize(expected));
+            prop_assert!(items.is_finished());
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use std::ops::Range;
+
+use crate::parser::{CharType, ParseSettings, Parser, ScanVisitor};
+use crate::pointer::JsonPath;
+use crate::value_parser::{is_space, unescape_string};
+use crate::Allow;
+
+/// 补全结果的详细信息，`text`总是等于`输入[..kept_prefix_len] + suffix`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    /// 补全之后的完整json
+    pub text: String,
+    /// 原样保留的输入前缀的字节数
+    pub kept_prefix_len: usize,
+    /// 输入中没有原样出现在结果里的部分，即`kept_prefix_len..输入长度`
+    pub dropped: Range<usize>,
+    /// 接在保留的前缀之后的内容，包括补全的原子值以及闭合的括号
+    pub suffix: String,
+    /// 尚未结束的值的 JSON Pointer，由外向内
+    pub incomplete_paths: Vec<String>,
+}
+
+impl Completion {
+    /// `text`中从`pending`开始都是尚未确定的内容
+    pub(crate) fn new(src: &str, text: String, pending: usize) -> Completion {
+        let mut collector = PathCollector {
+            pending,
+            ..Default::default()
+        };
+        let mut parser = Parser::with_settings(&text, ParseSettings::from(Allow::ALL));
+        parser.parse_with(&mut collector);
+        collector.finish(&text);
+        collector.paths.reverse();
+        Self::with_paths(src, text, collector.paths)
+    }
+
+    /// 把补全的结果换成`text`，例如还原为方言之后的结果，尚未结束的值保持不变
+    pub(crate) fn with_text(self, src: &str, text: String) -> Completion {
+        Self::with_paths(src, text, self.incomplete_paths)
+    }
+
+    fn with_paths(src: &str, text: String, incomplete_paths: Vec<String>) -> Completion {
+        let kept_prefix_len = src
+            .char_indices()
+            .zip(text.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(src.len().min(text.len()), |((idx, _), _)| idx);
+        Completion {
+            suffix: text[kept_prefix_len..].to_string(),
+            dropped: kept_prefix_len..src.len(),
+            kept_prefix_len,
+            text,
+            incomplete_paths,
+        }
+    }
+}
+
+/// 扫描补全之后的json，记录结束位置在`pending`之后的值
+#[derive(Default)]
+struct PathCollector {
+    pending: usize,
+    path: JsonPath,
+    last: usize,
+    paths: Vec<String>,
+}
+
+impl PathCollector {
+    /// 处理`self.last..idx`之间的原子值，返回去掉空白之后的内容
+    fn atom<'a>(&mut self, src: &'a str, idx: usize, is_key: bool) -> &'a str {
+        let raw = src[self.last..idx].trim_end_matches(is_space);
+        let atom = raw.trim_start_matches(is_space);
+        if !is_key && !atom.is_empty() && self.last + raw.len() > self.pending {
+            self.paths.push(self.path.pointer());
+        }
+        atom
+    }
+
+    fn finish(&mut self, src: &str) {
+        // 根节点是原子值时没有任何结构字符
+        self.atom(src, src.len(), false);
+    }
+}
+
+impl ScanVisitor for PathCollector {
+    fn visit(&mut self, src: &str, idx: usize, char_type: CharType) {
+        let atom = self.atom(src, idx, char_type == CharType::Colon);
+        self.last = idx + 1;
+        match char_type {
+            CharType::Colon => self.path.set_key(unescape_string(atom)),
+            CharType::Comma => self.path.next(),
+            CharType::LFB => self.path.enter_array(),
+            CharType::LCB => self.path.enter_object(),
+            CharType::RFB | CharType::RCB => {
+                self.path.leave();
+                if idx >= self.pending {
+                    self.paths.push(self.path.pointer());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Allow, Parser};
+
+    #[test]
+    fn test_completion() {
+        let src = r#"{"a": [1, {"b/c": "x"#;
+        let res = Parser::completion_with(src, Allow::ALL).unwrap();
+        assert_eq!(res.text, r#"{"a": [1, {"b/c":"x"}]}"#);
+        assert_eq!(res.kept_prefix_len, 17);
+        assert_eq!(res.dropped, 17..src.len());
+        assert_eq!(res.suffix, r#""x"}]}"#);
+        assert_eq!(res.incomplete_paths, ["", "/a", "/a/1", "/a/1/b~1c"]);
+
+        // 最后一个值已经结束，但是外层的括号还没有闭合
+        let res = Parser::completion_with(r#"[[1, 2], "x""#, Allow::ALL).unwrap();
+        assert_eq!(res.text, r#"[[1, 2],"x"]"#);
+        assert_eq!(res.suffix, r#""x"]"#);
+        assert_eq!(res.incomplete_paths, [""]);
+
+        let res = Parser::completion_with(r#"{"a": 1, "b"#, Allow::ALL).unwrap();
+        assert_eq!(res.text, r#"{"a": 1}"#);
+        assert_eq!(res.dropped, 7..11);
+        assert_eq!(res.incomplete_paths, [""]);
+
+        let res = Parser::completion_with("12", Allow::ALL).unwrap();
+        assert_eq!((res.kept_prefix_len, res.suffix.as_str()), (2, ""));
+        assert_eq!(res.incomplete_paths, [""]);
+    }
+}
@@ -0,0 +1,507 @@
+use std::fmt;
+
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+use serde_json::error::Category;
+use serde_json::Value;
+
+use crate::completion::Completion;
+
+use crate::parser::{ParseSettings, Parser};
+use crate::{Allow, Error};
+
+/// [`from_partial_str`]的错误
+#[derive(Debug)]
+pub enum FromPartialError {
+    /// 输入本身无法补全
+    Parse(Error),
+    /// 目前的数据还不够，继续接收输入之后可能会成功，例如缺少尚未到达的字段
+    Incomplete(serde_json::Error),
+    /// 已经结束的部分与目标类型不符，无论后续输入是什么都不会成功
+    Mismatch(serde_json::Error),
+}
+
+impl FromPartialError {
+    pub fn is_incomplete(&self) -> bool {
+        matches!(self, Self::Incomplete(_))
+    }
+}
+
+impl fmt::Display for FromPartialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(s) => write!(f, "{}", s),
+            Self::Incomplete(err) => write!(f, "incomplete input: {}", err),
+            Self::Mismatch(err) => write!(f, "mismatched input: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for FromPartialError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Parse(err) => Some(err),
+            Self::Incomplete(err) | Self::Mismatch(err) => Some(err),
+        }
+    }
+}
+
+/// 将残缺的json反序列化为`T`，尚未出现的字段视为缺失，
+/// 因此`Option`以及带有`#[serde(default)]`的字段在流式输出的过程中也可以使用。
+pub fn from_partial_str<T: DeserializeOwned>(in_str: &str) -> Result<T, FromPartialError> {
+    from_partial_str_with(in_str, Allow::ALL)
+}
+
+pub fn from_partial_str_with<T: DeserializeOwned>(
+    in_str: &str,
+    settings: impl Into<ParseSettings>,
+) -> Result<T, FromPartialError> {
+    if in_str.is_empty() {
+        return Err(FromPartialError::Parse(Error::Empty));
+    }
+    let mut parser = Parser::with_settings(in_str, settings.into());
+    parser.parse();
+    let (amended, pending) = parser
+        .amend_with_pending()
+        .map_err(FromPartialError::Parse)?;
+    let non_finite = parser.settings().non_finite;
+    let pending = non_finite
+        .apply_text(&amended[..pending])
+        .map_err(FromPartialError::Parse)?
+        .len();
+    let amended = non_finite
+        .apply_text(&amended)
+        .map_err(FromPartialError::Parse)?;
+
+    serde_json::from_str(&amended).map_err(|err| {
+        // 只有发生在尚未确定的部分、并且不是类型错误时才有机会随着后续的输入消失，
+        // 例如缺少字段或者枚举值只收到了一半；字符串无论如何都不会变成数字
+        let incomplete = match probe::<T>(&amended, pending) {
+            Some(incomplete) => incomplete,
+            // 两种方式的结果不一致时，按照错误的位置判断
+            None => err.classify() == Category::Data && error_offset(&amended, &err) >= pending,
+        };
+        if incomplete {
+            FromPartialError::Incomplete(err)
+        } else {
+            FromPartialError::Mismatch(err)
+        }
+    })
+}
+
+/// 用补全后的值重新反序列化一遍，返回错误能否随着后续的输入消失；没有出错时返回`None`
+fn probe<T: DeserializeOwned>(amended: &str, pending: usize) -> Option<bool> {
+    let value: Value = serde_json::from_str(amended).ok()?;
+    let pending_paths = Completion::new(amended, amended.to_string(), pending).incomplete_paths;
+    let probe = Probe {
+        value: &value,
+        path: String::new(),
+        pending: &pending_paths,
+    };
+    T::deserialize(probe)
+        .err()
+        .map(|err| err.incomplete == Some(true))
+}
+
+/// [`Probe`]的错误，记录错误是否只与尚未确定的部分有关
+#[derive(Debug)]
+struct ProbeError {
+    msg: String,
+    // 类型错误以及多余的元素、未知的字段与后续的输入无关
+    fixed: bool,
+    // 由最内层出错的值决定
+    incomplete: Option<bool>,
+}
+
+impl ProbeError {
+    fn fixed(msg: String) -> ProbeError {
+        ProbeError {
+            msg,
+            fixed: true,
+            incomplete: None,
+        }
+    }
+}
+
+impl fmt::Display for ProbeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.msg)
+    }
+}
+
+impl std::error::Error for ProbeError {}
+
+impl de::Error for ProbeError {
+    fn custom<M: fmt::Display>(msg: M) -> Self {
+        ProbeError {
+            msg: msg.to_string(),
+            fixed: false,
+            incomplete: None,
+        }
+    }
+
+    fn invalid_type(unexp: de::Unexpected, exp: &dyn de::Expected) -> Self {
+        ProbeError::fixed(format!("invalid type: {}, expected {}", unexp, exp))
+    }
+
+    fn invalid_value(unexp: de::Unexpected, exp: &dyn de::Expected) -> Self {
+        let msg = format!("invalid value: {}, expected {}", unexp, exp);
+        match unexp {
+            // 尚未结束的字符串之后还可能变得合法，由它是否已经结束决定
+            de::Unexpected::Str(_) | de::Unexpected::Char(_) | de::Unexpected::Bytes(_) => {
+                ProbeError::custom(msg)
+            }
+            // 数字只会越来越长，超出范围以及符号不对的数字不会随着后续的输入变得合法
+            _ => ProbeError::fixed(msg),
+        }
+    }
+
+    fn invalid_length(len: usize, exp: &dyn de::Expected) -> Self {
+        // 元素过多的情况已经在`Probe`中作为`fixed`处理，元素不足时只有尚未结束的数组还会增加元素，
+        // 已经结束的数组由`Probe::tag`判断为无法改变
+        ProbeError::custom(format!("invalid length {}, expected {}", len, exp))
+    }
+
+    fn unknown_field(field: &str, expected: &'static [&'static str]) -> Self {
+        ProbeError::fixed(format!(
+            "unknown field `{}`, expected {:?}",
+            field, expected
+        ))
+    }
+}
+
+/// 所有的类型检查都交给visitor完成的反序列化器，因此类型错误一定经过[`ProbeError::invalid_type`]，
+/// 不需要根据错误信息的文本来判断
+struct Probe<'a> {
+    value: &'a Value,
+    // 当前值的 JSON Pointer
+    path: String,
+    pending: &'a [String],
+}
+
+impl<'a> Probe<'a> {
+    fn child(&self, value: &'a Value, seg: &str) -> Probe<'a> {
+        Probe {
+            value,
+            path: format!(
+                "{}/{}",
+                self.path,
+                seg.replace('~', "~0").replace('/', "~1")
+            ),
+            pending: self.pending,
+        }
+    }
+
+    /// 最内层出错的值决定错误能否随着后续的输入消失
+    fn tag<V>(&self, res: Result<V, ProbeError>) -> Result<V, ProbeError> {
+        res.map_err(|mut err| {
+            if err.incomplete.is_none() {
+                err.incomplete = Some(!err.fixed && self.pending.contains(&self.path));
+            }
+            err
+        })
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Probe<'_> {
+    type Error = ProbeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ProbeError> {
+        let res = match self.value {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(b) => visitor.visit_bool(*b),
+            Value::Number(n) => match (n.as_u64(), n.as_i64(), n.as_f64()) {
+                (Some(n), _, _) => visitor.visit_u64(n),
+                (_, Some(n), _) => visitor.visit_i64(n),
+                (_, _, n) => visitor.visit_f64(n.unwrap_or(f64::NAN)),
+            },
+            Value::String(s) => visitor.visit_str(s),
+            Value::Array(arr) => {
+                let mut seq = SeqProbe {
+                    probe: &self,
+                    iter: arr.iter().enumerate(),
+                };
+                visitor
+                    .visit_seq(&mut seq)
+                    .and_then(|res| match seq.iter.len() {
+                        0 => Ok(res),
+                        _ => Err(ProbeError::fixed(format!(
+                            "invalid length {}, expected fewer elements",
+                            arr.len()
+                        ))),
+                    })
+            }
+            Value::Object(obj) => visitor.visit_map(MapProbe {
+                probe: &self,
+                iter: obj.iter(),
+                value: None,
+            }),
+        };
+        self.tag(res)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ProbeError> {
+        match self.value {
+            Value::Null => self.tag(visitor.visit_none()),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, ProbeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ProbeError> {
+        let res = match self.value {
+            Value::String(s) => visitor.visit_enum(s.as_str().into_deserializer()),
+            Value::Object(obj) if obj.len() == 1 => {
+                let (variant, value) = obj.iter().next().unwrap();
+                visitor.visit_enum(EnumProbe {
+                    variant,
+                    probe: self.child(value, variant),
+                })
+            }
+            _ => return self.deserialize_any(visitor),
+        };
+        self.tag(res)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct SeqProbe<'p, 'a, I> {
+    probe: &'p Probe<'a>,
+    iter: I,
+}
+
+impl<'de, 'a, I> SeqAccess<'de> for SeqProbe<'_, 'a, I>
+where
+    I: ExactSizeIterator<Item = (usize, &'a Value)>,
+{
+    type Error = ProbeError;
+
+    fn next_element_seed<S: DeserializeSeed<'de>>(
+        &mut self,
+        seed: S,
+    ) -> Result<Option<S::Value>, ProbeError> {
+        match self.iter.next() {
+            Some((idx, value)) => seed
+                .deserialize(self.probe.child(value, &idx.to_string()))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+struct MapProbe<'p, 'a> {
+    probe: &'p Probe<'a>,
+    iter: serde_json::map::Iter<'a>,
+    value: Option<(&'a String, &'a Value)>,
+}
+
+impl<'de> MapAccess<'de> for MapProbe<'_, '_> {
+    type Error = ProbeError;
+
+    fn next_key_seed<S: DeserializeSeed<'de>>(
+        &mut self,
+        seed: S,
+    ) -> Result<Option<S::Value>, ProbeError> {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.value = Some((key, value));
+                seed.deserialize(key.as_str().into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<S: DeserializeSeed<'de>>(
+        &mut self,
+        seed: S,
+    ) -> Result<S::Value, ProbeError> {
+        let (key, value) = self.value.take().expect("value is missing");
+        seed.deserialize(self.probe.child(value, key))
+    }
+}
+
+/// `{"Variant": value}`形式的枚举值
+struct EnumProbe<'a> {
+    variant: &'a str,
+    probe: Probe<'a>,
+}
+
+impl<'de, 'a> EnumAccess<'de> for EnumProbe<'a> {
+    type Error = ProbeError;
+    type Variant = Probe<'a>;
+
+    fn variant_seed<S: DeserializeSeed<'de>>(
+        self,
+        seed: S,
+    ) -> Result<(S::Value, Probe<'a>), ProbeError> {
+        let variant = seed.deserialize(self.variant.into_deserializer())?;
+        Ok((variant, self.probe))
+    }
+}
+
+impl<'de> VariantAccess<'de> for Probe<'_> {
+    type Error = ProbeError;
+
+    fn unit_variant(self) -> Result<(), ProbeError> {
+        de::Deserialize::deserialize(self)
+    }
+
+    fn newtype_variant_seed<S: DeserializeSeed<'de>>(
+        self,
+        seed: S,
+    ) -> Result<S::Value, ProbeError> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, ProbeError> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ProbeError> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
+
+/// 将`serde_json`给出的行列号转换为字节偏移，列号从1开始并且以字节计
+fn error_offset(s: &str, err: &serde_json::Error) -> usize {
+    let line_start: usize = s
+        .split_inclusive('\n')
+        .take(err.line().saturating_sub(1))
+        .map(str::len)
+        .sum();
+    line_start + err.column().saturating_sub(1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum Status {
+        #[serde(rename = "active")]
+        Active,
+        #[serde(rename = "inactive")]
+        Inactive,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum Shape {
+        Circle(f64),
+        Rect { w: u32, h: u32 },
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct User {
+        name: String,
+        age: Option<u32>,
+        #[serde(default)]
+        tags: Vec<String>,
+        status: Option<Status>,
+    }
+
+    #[test]
+    fn test_from_partial_str() {
+        let user: User = from_partial_str(r#"{"name": "Ali"#).unwrap();
+        assert_eq!(
+            user,
+            User {
+                name: "Ali".to_string(),
+                age: None,
+                tags: vec![],
+                status: None,
+            }
+        );
+
+        let user: User =
+            from_partial_str(r#"{"name": "Alice", "age": 30, "tags": ["a", "b"#).unwrap();
+        assert_eq!(user.age, Some(30));
+        assert_eq!(user.tags, ["a", "b"]);
+
+        let user: User =
+            from_partial_str(r#"{"name": "Alice", "status": "inactive", "ag"#).unwrap();
+        assert_eq!(user.status, Some(Status::Inactive));
+    }
+
+    #[test]
+    fn test_incomplete() {
+        // 必需的字段尚未到达
+        let err = from_partial_str::<User>(r#"{"age": 30, "na"#).unwrap_err();
+        assert!(err.is_incomplete(), "{}", err);
+        // 枚举值只收到了一部分
+        let err = from_partial_str::<User>(r#"{"name": "A", "status": "act"#).unwrap_err();
+        assert!(err.is_incomplete(), "{}", err);
+        let err = from_partial_str::<[u32; 3]>("[1, 2").unwrap_err();
+        assert!(err.is_incomplete(), "{}", err);
+        let err = from_partial_str::<Vec<(u8, u8)>>("[[1").unwrap_err();
+        assert!(err.is_incomplete(), "{}", err);
+        // 枚举的内容中缺少字段
+        let err = from_partial_str::<Shape>(r#"{"Rect": {"w": 1, "h"#).unwrap_err();
+        assert!(err.is_incomplete(), "{}", err);
+    }
+
+    #[test]
+    fn test_mismatch() {
+        // 对象已经结束，却缺少必需的字段
+        let err = from_partial_str::<User>(r#"{"age": 30}"#).unwrap_err();
+        assert!(matches!(err, FromPartialError::Mismatch(_)), "{}", err);
+        // 字符串无论如何都不会变成数字
+        let err = from_partial_str::<User>(r#"{"name": "A", "age": "3"#).unwrap_err();
+        assert!(matches!(err, FromPartialError::Mismatch(_)), "{}", err);
+        let err = from_partial_str::<User>("{\"name\": \"A\",\n \"status\": \"unknown\", \"ag")
+            .unwrap_err();
+        assert!(matches!(err, FromPartialError::Mismatch(_)), "{}", err);
+        // 残缺的数字也不会变成字符串
+        let err = from_partial_str::<User>(r#"{"name": 12"#).unwrap_err();
+        assert!(matches!(err, FromPartialError::Mismatch(_)), "{}", err);
+        let err = from_partial_str::<Shape>(r#"{"Circle": "1"#).unwrap_err();
+        assert!(matches!(err, FromPartialError::Mismatch(_)), "{}", err);
+        let err = from_partial_str::<User>(r#"{"name": "A", "age": tr"#).unwrap_err();
+        assert!(matches!(err, FromPartialError::Mismatch(_)), "{}", err);
+        let err = from_partial_str::<User>(r#"{"name": ["#).unwrap_err();
+        assert!(matches!(err, FromPartialError::Mismatch(_)), "{}", err);
+        let err = from_partial_str::<[u32; 1]>("[1, 2, 3").unwrap_err();
+        assert!(matches!(err, FromPartialError::Mismatch(_)), "{}", err);
+        // 超出范围的数字以及已经结束、长度不对的元组
+        let err = from_partial_str::<User>(r#"{"name": "A", "age": 5000000000"#).unwrap_err();
+        assert!(matches!(err, FromPartialError::Mismatch(_)), "{}", err);
+        let err = from_partial_str::<Vec<u8>>("[300").unwrap_err();
+        assert!(matches!(err, FromPartialError::Mismatch(_)), "{}", err);
+        let err = from_partial_str::<Vec<u32>>("[-1").unwrap_err();
+        assert!(matches!(err, FromPartialError::Mismatch(_)), "{}", err);
+        let err = from_partial_str::<Vec<(u8, u8)>>("[[1], ").unwrap_err();
+        assert!(matches!(err, FromPartialError::Mismatch(_)), "{}", err);
+        let err = from_partial_str::<Vec<(u8, u8)>>("[[1, 2, 3]").unwrap_err();
+        assert!(matches!(err, FromPartialError::Mismatch(_)), "{}", err);
+        assert!(matches!(
+            from_partial_str::<User>(""),
+            Err(FromPartialError::Parse(_))
+        ));
+    }
+}
@@ -0,0 +1,59 @@
+use std::fmt;
+
+use crate::utils::add_title;
+use crate::Error;
+
+/// 不影响补全结果、但调用者可能关心的情况，offset都是输入中的字节序
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning {
+    /// 宽松模式下，`offset`处不合法转义的反斜杠被当作普通字符
+    RepairedEscape { offset: usize },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RepairedEscape { offset } => write!(f, "repaired invalid escape at {}", offset),
+        }
+    }
+}
+
+/// 扫描之后解析器内部状态的快照，用于调试
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTrace {
+    /// 尚未闭合的左括号及其位置，由外向内
+    pub stack: Vec<(usize, char)>,
+    /// 输入是否结束在字符串内部
+    pub in_string: bool,
+    pub last_sep: Option<usize>,
+    pub last_colon: Option<usize>,
+    pub last_rbracket: Option<usize>,
+    pub error: Option<Error>,
+    pub warnings: Vec<Warning>,
+}
+
+impl fmt::Display for ParseTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&add_title("Parse Tracer"))?;
+        f.write_str(&add_title("Stack Tracer"))?;
+        for (idx, (c_idx, item)) in self.stack.iter().enumerate() {
+            writeln!(f, "idx: {}, item {} at {} of str", idx, item, c_idx)?;
+        }
+        f.write_str(&add_title("State"))?;
+        writeln!(f, "in_string: {}", self.in_string)?;
+        f.write_str(&add_title("Last Sep"))?;
+        writeln!(
+            f,
+            "sep: {:?}, colon: {:?}, rbracket: {:?}",
+            self.last_sep, self.last_colon, self.last_rbracket
+        )?;
+        if let Some(err) = &self.error {
+            f.write_str(&add_title("Parse State"))?;
+            writeln!(f, "{}", err)?;
+        }
+        for warning in &self.warnings {
+            writeln!(f, "Warning: {}", warning)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,928 @@
+use std::ops::Range;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::documents::find_value_end;
+
+/// 输入的方言，非json的输入会先被翻译为json再交给原有的状态机处理
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    #[default]
+    Json,
+    /// 单引号字符串、不带引号的key、末尾逗号、注释、十六进制以及`+1`、`.5`这样的数字、多行字符串
+    Json5,
+    /// Python字面量：`True`/`False`/`None`、单引号、三引号以及带前缀的字符串、作为数组的元组，
+    /// 总是补全为严格的json。`(1)`这样没有逗号的括号不是元组，`1j`等无法表示为json的字面量返回错误
+    PythonLiteral,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TState {
+    Normal,
+    Str {
+        quote: char,
+        raw: bool,
+        triple: bool,
+    },
+    LineComment,
+    BlockComment,
+}
+
+/// 增量地把方言翻译为json。
+///
+/// 已经确定的部分只会追加、不会修改，因此翻译结果可以直接交给增量扫描；
+/// 末尾尚未结束的数字、key以及转义会留在`pending`中，Python中尚未确定是元组还是括号的`(`
+/// 之后的翻译结果留在`deferred`中，由`tail`给出它们残缺的翻译。
+#[derive(Debug, Clone)]
+pub(crate) struct Translator {
+    dialect: Dialect,
+    state: TState,
+    // 尚未翻译的输入，以及它在整个输入中的起始位置
+    pending: String,
+    pending_start: usize,
+    // 每一层是否是对象，以及当前是否在等待key
+    stack: Vec<bool>,
+    expect_key: bool,
+    // Python中刚刚读到key，之后只能是冒号
+    after_key: bool,
+    // 刚刚读到Python的原始字符串前缀`r`
+    raw_string: bool,
+    // Python中尚未确定是元组还是括号的`(`，由外向内，以及最外层的`(`之后已经翻译的内容
+    parens: Vec<Paren>,
+    deferred: String,
+    // 第一个不支持的字面量在翻译结果中的位置
+    error: Option<usize>,
+    // 逗号要等到下一个有效字符到达才能确定是不是末尾的逗号，之后的空白也一起保留
+    held: Option<String>,
+    out_len: usize,
+    // 每个token在输入以及翻译结果中的起始位置，用于把补全的结果还原为原本的方言
+    boundaries: Vec<(usize, usize)>,
+}
+
+impl Translator {
+    pub fn new(dialect: Dialect) -> Translator {
+        Translator {
+            dialect,
+            state: TState::Normal,
+            pending: String::new(),
+            pending_start: 0,
+            stack: Vec::new(),
+            expect_key: false,
+            after_key: false,
+            raw_string: false,
+            parens: Vec::new(),
+            deferred: String::new(),
+            error: None,
+            held: None,
+            out_len: 0,
+            boundaries: Vec::new(),
+        }
+    }
+
+    /// 追加输入，返回新确定下来的翻译结果
+    pub fn push(&mut self, chunk: &str) -> String {
+        self.pending.push_str(chunk);
+        let mut out = String::new();
+        self.process(&mut out, false);
+        out
+    }
+
+    /// 把尚未确定的部分当作输入的结尾进行翻译，不修改自身的状态
+    pub fn tail(&self) -> String {
+        self.finish(Vec::new()).1
+    }
+
+    fn finish(&self, boundaries: Vec<(usize, usize)>) -> (Translator, String) {
+        let mut translator = Translator {
+            boundaries,
+            pending: self.pending.clone(),
+            stack: self.stack.clone(),
+            parens: self.parens.clone(),
+            deferred: self.deferred.clone(),
+            held: self.held.clone(),
+            ..*self
+        };
+        let mut out = String::new();
+        translator.process(&mut out, true);
+        // 输入结束时仍未确定的`(`都是括号
+        translator.parens.clear();
+        out.push_str(&std::mem::take(&mut translator.deferred));
+        translator.flush_held(&mut out);
+        (translator, out)
+    }
+
+    /// 翻译结果（包括`tail`）中的位置对应的输入中的位置，
+    /// 以所在token的起始位置为准，不会越过下一个token
+    pub fn source_offset(&self, source: &str, offset: usize) -> usize {
+        // 之后还有已经确定的token时，不需要处理末尾尚未确定的部分
+        if self
+            .boundaries
+            .last()
+            .is_some_and(|(_, out_idx)| *out_idx > offset)
+        {
+            return Self::offset_in(&self.boundaries, source, offset);
+        }
+        let (translator, _) = self.finish(self.boundaries.clone());
+        Self::offset_in(&translator.boundaries, source, offset)
+    }
+
+    fn offset_in(boundaries: &[(usize, usize)], source: &str, offset: usize) -> usize {
+        let pos = boundaries.partition_point(|(_, out_idx)| *out_idx <= offset);
+        let (src_idx, out_idx) = pos.checked_sub(1).map_or((0, 0), |pos| boundaries[pos]);
+        let limit = boundaries
+            .get(pos)
+            .map_or(source.len(), |(src_idx, _)| *src_idx);
+        (src_idx + offset - out_idx).min(limit)
+    }
+
+    /// `text`是完整的翻译结果，`completed`是由它补全得到的json，
+    /// 从两者开始不同的位置向前找到一个token的边界，边界之前使用原本的输入
+    pub fn to_source(&self, source: &str, text: &str, completed: &str) -> String {
+        let common = text
+            .char_indices()
+            .zip(completed.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(text.len().min(completed.len()), |((idx, _), _)| idx);
+        let (src_idx, out_idx) = self
+            .boundaries
+            .iter()
+            .rev()
+            .find(|(_, out_idx)| *out_idx <= common)
+            .copied()
+            .unwrap_or((0, 0));
+        source[..src_idx].to_string() + &completed[out_idx..]
+    }
+
+    /// 不支持的字面量在翻译结果中的位置，包括末尾尚未确定的部分
+    pub fn error(&self) -> Option<usize> {
+        self.error.or_else(|| self.finish(Vec::new()).0.error)
+    }
+
+    /// 已经确定的输入中不支持的字面量的位置，不需要翻译末尾尚未确定的部分
+    pub fn committed_error(&self) -> Option<usize> {
+        self.error
+    }
+
+    fn process(&mut self, out: &mut String, is_final: bool) {
+        if self.error.is_some() {
+            return;
+        }
+        let pending = std::mem::take(&mut self.pending);
+        let mut idx = 0;
+        while idx < pending.len() {
+            match self.step(&pending[idx..], self.pending_start + idx, out, is_final) {
+                Some(consumed) => idx += consumed,
+                None => break,
+            }
+        }
+        self.pending = pending[idx..].to_string();
+        self.pending_start += idx;
+    }
+
+    /// 写入翻译结果，存在尚未确定的`(`时写入`deferred`
+    fn write(&mut self, out: &mut String, s: &str) {
+        match self.parens.is_empty() {
+            true => out.push_str(s),
+            false => self.deferred.push_str(s),
+        }
+        self.out_len += s.len();
+    }
+
+    fn emit(&mut self, out: &mut String, src_idx: usize, s: &str) {
+        self.flush_held(out);
+        self.boundaries.push((src_idx, self.out_len));
+        if let Some(paren) = self.parens.last_mut() {
+            paren.content = true;
+        }
+        self.write(out, s);
+    }
+
+    /// 空白以及注释，跟在保留的逗号之后时一起保留
+    fn emit_space(&mut self, out: &mut String, s: &str) {
+        match &mut self.held {
+            Some(held) => held.push_str(s),
+            None => self.write(out, s),
+        }
+    }
+
+    fn flush_held(&mut self, out: &mut String) {
+        if let Some(held) = self.held.take() {
+            self.write(out, &held);
+        }
+    }
+
+    /// 最内层的`(`已经确定，所有的`(`都确定之后把`deferred`写入翻译结果
+    fn settle_paren(&mut self, out: &mut String) -> Paren {
+        let paren = self.parens.pop().unwrap();
+        if self.parens.is_empty() {
+            out.push_str(&std::mem::take(&mut self.deferred));
+        }
+        paren
+    }
+
+    /// 尚未确定的`(`之中出现了同一层的逗号，它是元组，在它的位置补上`[`
+    fn settle_tuple(&mut self, out: &mut String) {
+        let paren = self.parens.last().unwrap();
+        let idx = paren.out_idx - (self.out_len - self.deferred.len());
+        self.deferred.insert(idx, '[');
+        self.out_len += 1;
+        for (_, out_idx) in &mut self.boundaries[paren.boundary..] {
+            *out_idx += 1;
+        }
+        self.boundaries
+            .insert(paren.boundary, (paren.src_idx, paren.out_idx));
+        self.settle_paren(out);
+    }
+
+    /// 尚未确定的`(`在同一层结束：没有内容时是空的元组，否则只是括号
+    fn settle_group(&mut self, out: &mut String, src_idx: usize) {
+        let paren = self.settle_paren(out);
+        self.stack.pop();
+        self.expect_key = false;
+        if !paren.content {
+            self.emit(out, paren.src_idx, "[");
+            self.emit(out, src_idx, "]");
+        }
+    }
+
+    /// 最内层尚未确定的`(`是否就是当前的这一层
+    fn in_paren(&self) -> bool {
+        self.parens
+            .last()
+            .is_some_and(|paren| paren.depth == self.stack.len())
+    }
+
+    fn is_delimiter(&self, c: char) -> bool {
+        is_json5_space(c)
+            || matches!(c, ',' | ':' | '[' | ']' | '{' | '}' | '"' | '\'' | '/')
+            || (self.is_python() && matches!(c, '(' | ')'))
+    }
+
+    fn is_python(&self) -> bool {
+        self.dialect == Dialect::PythonLiteral
+    }
+
+    fn in_key(&self) -> bool {
+        self.expect_key && self.stack.last() == Some(&true)
+    }
+
+    /// 处理`rest`开头的内容，返回消耗的字节数，需要更多的输入时返回`None`
+    fn step(
+        &mut self,
+        rest: &str,
+        src_idx: usize,
+        out: &mut String,
+        is_final: bool,
+    ) -> Option<usize> {
+        let mut chars = rest.chars();
+        let c = chars.next()?;
+        let next = chars.next();
+        match self.state {
+            TState::LineComment => {
+                if c == '\n' {
+                    self.state = TState::Normal;
+                    return Some(0);
+                }
+                Some(c.len_utf8())
+            }
+            TState::BlockComment => {
+                if c == '*' && next == Some('/') {
+                    self.state = TState::Normal;
+                    self.emit_space(out, " ");
+                    Some(2)
+                } else if c == '*' && next.is_none() && !is_final {
+                    None
+                } else {
+                    Some(c.len_utf8())
+                }
+            }
+            TState::Str { quote, raw, triple } => {
+                self.step_str(rest, quote, raw, triple, out, is_final)
+            }
+            TState::Normal => match c {
+                _ if is_json5_space(c) => {
+                    // json中只有这四种空白，其它的空白换成空格
+                    let space = match c {
+                        ' ' | '\t' | '\r' | '\n' => &rest[..1],
+                        _ => " ",
+                    };
+                    self.emit_space(out, space);
+                    Some(c.len_utf8())
+                }
+                // Python的集合`{1, 2}`，以及作为key的元组等json中无法表示的key
+                ':' if self.after_key => {
+                    self.after_key = false;
+                    self.emit(out, src_idx, ":");
+                    self.expect_key = false;
+                    Some(1)
+                }
+                _ if self.after_key => self.fail(out, src_idx),
+                '(' | '[' | '{' if self.is_python() && self.in_key() => self.fail(out, src_idx),
+                '/' if self.dialect == Dialect::Json5 => match next {
+                    Some('/') => {
+                        self.state = TState::LineComment;
+                        Some(2)
+                    }
+                    Some('*') => {
+                        self.state = TState::BlockComment;
+                        Some(2)
+                    }
+                    None if !is_final => None,
+                    None => Some(1),
+                    Some(_) => {
+                        self.emit(out, src_idx, "/");
+                        Some(1)
+                    }
+                },
+                '"' | '\'' => {
+                    // Python的三引号字符串，只读到两个引号时还无法确定是不是空字符串
+                    let quotes = rest.chars().take(3).take_while(|q| *q == c).count();
+                    if self.is_python() && quotes < 3 && quotes == rest.len() && !is_final {
+                        return None;
+                    }
+                    let triple = self.is_python() && quotes == 3;
+                    self.after_key = self.is_python() && self.in_key();
+                    self.emit(out, src_idx, "\"");
+                    self.state = TState::Str {
+                        quote: c,
+                        raw: std::mem::take(&mut self.raw_string),
+                        triple,
+                    };
+                    Some(if triple { 3 } else { 1 })
+                }
+                '(' if self.is_python() => {
+                    // 出现同一层的逗号之后才是元组，否则只是括号，在此之前的翻译结果暂时保留
+                    self.flush_held(out);
+                    if let Some(paren) = self.parens.last_mut() {
+                        paren.content = true;
+                    }
+                    self.stack.push(false);
+                    self.expect_key = false;
+                    self.parens.push(Paren {
+                        src_idx,
+                        out_idx: self.out_len,
+                        boundary: self.boundaries.len(),
+                        depth: self.stack.len(),
+                        content: false,
+                    });
+                    Some(1)
+                }
+                ')' if self.in_paren() => {
+                    self.settle_group(out, src_idx);
+                    Some(1)
+                }
+                // 不匹配的括号同样结束尚未确定的`(`，之后再作为普通的括号处理
+                '}' | ']' if self.in_paren() => {
+                    self.settle_group(out, src_idx);
+                    Some(0)
+                }
+                ',' if self.in_paren() => {
+                    self.settle_tuple(out);
+                    Some(0)
+                }
+                '{' | '[' | '(' if c != '(' || self.is_python() => {
+                    let bracket = if c == '{' { "{" } else { "[" };
+                    self.emit(out, src_idx, bracket);
+                    self.stack.push(c == '{');
+                    self.expect_key = c == '{';
+                    Some(1)
+                }
+                '}' | ']' | ')' if c != ')' || self.is_python() => {
+                    // 末尾的逗号被丢弃，之后的空白保留
+                    if let Some(held) = self.held.take() {
+                        self.emit_space(out, &held[1..]);
+                    }
+                    let bracket = if c == '}' { "}" } else { "]" };
+                    self.emit(out, src_idx, bracket);
+                    self.stack.pop();
+                    self.expect_key = false;
+                    Some(1)
+                }
+                ',' => {
+                    self.flush_held(out);
+                    self.boundaries.push((src_idx, self.out_len));
+                    self.held = Some(",".to_string());
+                    self.expect_key = true;
+                    Some(1)
+                }
+                ':' => {
+                    self.emit(out, src_idx, ":");
+                    self.expect_key = false;
+                    Some(1)
+                }
+                _ => {
+                    let len = rest
+                        .find(|c| self.is_delimiter(c))
+                        .unwrap_or(rest.len())
+                        .max(c.len_utf8());
+                    let complete = len < rest.len();
+                    if !complete && !is_final {
+                        return None;
+                    }
+                    if self.is_python() && is_string_prefix(&rest[..len]) {
+                        match rest[len..].chars().next() {
+                            Some('\'' | '"') => {
+                                self.raw_string = rest[..len].contains(['r', 'R']);
+                                return Some(len);
+                            }
+                            // 输入在前缀之后结束
+                            None => return Some(len),
+                            _ => {}
+                        }
+                    }
+                    let Some(atom) = self.translate_atom(&rest[..len], complete) else {
+                        // `1j`这样无法翻译为json的字面量
+                        return self.fail(out, src_idx);
+                    };
+                    self.after_key = self.is_python() && self.in_key();
+                    self.emit(out, src_idx, &atom);
+                    Some(len)
+                }
+            },
+        }
+    }
+
+    /// 记录`src_idx`处无法翻译为json的内容，之后的输入都不再翻译
+    fn fail(&mut self, out: &mut String, src_idx: usize) -> Option<usize> {
+        self.flush_held(out);
+        self.boundaries.push((src_idx, self.out_len));
+        self.error = Some(self.out_len);
+        None
+    }
+
+    fn step_str(
+        &mut self,
+        rest: &str,
+        quote: char,
+        raw: bool,
+        triple: bool,
+        out: &mut String,
+        is_final: bool,
+    ) -> Option<usize> {
+        let mut chars = rest.chars();
+        let c = chars.next()?;
+        let quotes = rest.chars().take(3).take_while(|q| *q == quote).count();
+        if c == quote && (!triple || quotes == 3) {
+            self.state = TState::Normal;
+            self.write(out, "\"");
+            return Some(quotes.min(if triple { 3 } else { 1 }));
+        }
+        if triple && quotes > 0 && quotes == rest.len() {
+            // 可能是尚未完整到达的三个引号，输入结束时当作没有写完的结尾丢弃
+            return is_final.then_some(quotes);
+        }
+        let python = self.dialect == Dialect::PythonLiteral;
+        let mut push = |s: &str, out: &mut String| self.write(out, s);
+        if c != '\\' {
+            push(&escape_literal(&rest[..c.len_utf8()]), out);
+            return Some(c.len_utf8());
+        }
+
+        // 转义需要完整到达之后才能翻译，输入结束时丢弃残缺的转义
+        let wait = if is_final { Some(rest.len()) } else { None };
+        let Some(n) = chars.next() else {
+            return wait;
+        };
+        let end = 1 + n.len_utf8();
+        if raw {
+            // 原始字符串中的反斜杠是普通字符
+            push("\\\\", out);
+            push(&escape_literal(&rest[1..end]), out);
+            return Some(end);
+        }
+        let hex_escape = |len: usize| -> Option<Option<&str>> {
+            let hex = rest.get(2..2 + len)?;
+            Some(hex.chars().all(|c| c.is_ascii_hexdigit()).then_some(hex))
+        };
+        match n {
+            'x' | 'u' | 'U' if n != 'U' || python => {
+                let len = match n {
+                    'x' => 2,
+                    'u' => 4,
+                    _ => 8,
+                };
+                match hex_escape(len) {
+                    None if rest[2..].chars().all(|c| c.is_ascii_hexdigit()) => wait,
+                    Some(Some(hex)) if len < 8 => {
+                        push(&format!("\\u{:0>4}", hex), out);
+                        Some(2 + len)
+                    }
+                    Some(Some(hex)) => {
+                        match u32::from_str_radix(hex, 16).ok().and_then(char::from_u32) {
+                            // `\U0001F600`这样超出基本平面的字符直接写入
+                            Some(ch) if ch as u32 > 0xffff => {
+                                push(&ch.to_string(), out);
+                                Some(2 + len)
+                            }
+                            Some(ch) => {
+                                push(&format!("\\u{:04x}", ch as u32), out);
+                                Some(2 + len)
+                            }
+                            None => {
+                                push(&rest[1..2], out);
+                                Some(2)
+                            }
+                        }
+                    }
+                    // 不合法的十六进制转义视为普通字符
+                    _ => {
+                        push(&rest[1..2], out);
+                        Some(2)
+                    }
+                }
+            }
+            '\'' => {
+                push("'", out);
+                Some(2)
+            }
+            '"' | '\\' | 'b' | 'f' | 'n' | 'r' | 't' => {
+                push(&rest[..2], out);
+                Some(2)
+            }
+            '/' if !python => {
+                push(&rest[..2], out);
+                Some(2)
+            }
+            'v' => {
+                push("\\u000b", out);
+                Some(2)
+            }
+            'a' if python => {
+                push("\\u0007", out);
+                Some(2)
+            }
+            // Python中最多三位的八进制转义
+            '0'..='7' if python => {
+                let digits = rest[1..]
+                    .chars()
+                    .take(3)
+                    .take_while(|c| c.is_digit(8))
+                    .count();
+                if digits < 3 && 1 + digits == rest.len() && !is_final {
+                    return None;
+                }
+                let code = u32::from_str_radix(&rest[1..1 + digits], 8).unwrap_or_default();
+                push(&format!("\\u{:04x}", code), out);
+                Some(1 + digits)
+            }
+            '0' => {
+                push("\\u0000", out);
+                Some(2)
+            }
+            // 多行字符串，反斜杠之后的换行被忽略
+            '\r' if rest[2..].is_empty() && !is_final => None,
+            '\r' if rest[2..].starts_with('\n') => Some(3),
+            '\n' | '\r' | '\u{2028}' | '\u{2029}' => Some(end),
+            // Python中无法识别的转义保留反斜杠
+            _ if python => {
+                push("\\\\", out);
+                push(&rest[1..end], out);
+                Some(end)
+            }
+            _ => {
+                push(&rest[1..end], out);
+                Some(end)
+            }
+        }
+    }
+
+    /// 翻译字符串之外的原子值，Python中不支持的字面量返回`None`
+    fn translate_atom(&self, atom: &str, complete: bool) -> Option<String> {
+        let quote = if complete { "\"" } else { "" };
+        if self.is_python() {
+            let value = translate_python(atom, complete);
+            if !is_json_atom(&value, complete) {
+                return None;
+            }
+            // Python中没有不带引号的key，数字以及`True`等作为key时与`json.dumps`一样转换为字符串
+            return Some(match self.in_key() {
+                true => format!("\"{}{}", value, quote),
+                false => value,
+            });
+        }
+        if self.in_key() {
+            // JSON5中不带引号的key
+            return Some(format!("\"{}{}", atom, quote));
+        }
+        Some(match self.dialect {
+            Dialect::Json => atom.to_string(),
+            _ => translate_number(atom, complete),
+        })
+    }
+}
+
+/// JSON5中的空白，除了Unicode中的空白之外还包括BOM
+fn is_json5_space(c: char) -> bool {
+    c.is_whitespace() || c == '\u{feff}'
+}
+
+/// 字符串中的普通字符在json字符串中的写法，三引号字符串中可以直接出现换行，
+/// 其它控制字符同样需要转义
+fn escape_literal(c: &str) -> std::borrow::Cow<'_, str> {
+    match c {
+        "\"" => "\\\"".into(),
+        "\\" => "\\\\".into(),
+        "\n" => "\\n".into(),
+        "\r" => "\\r".into(),
+        "\t" => "\\t".into(),
+        "\u{8}" => "\\b".into(),
+        "\u{c}" => "\\f".into(),
+        _ if c.starts_with(|c| c < '\u{20}') => format!("\\u{:04x}", c.as_bytes()[0]).into(),
+        _ => c.into(),
+    }
+}
+
+/// 翻译之后的原子值是否是json的原子值，没有结束时只需要是某个原子值的前缀
+pub(crate) fn is_json_atom(atom: &str, complete: bool) -> bool {
+    let keywords = ["true", "false", "null", "NaN", "Infinity", "-Infinity"];
+    if complete {
+        keywords.contains(&atom) || JSON_NUMBER.is_match(atom)
+    } else {
+        keywords.iter().any(|keyword| keyword.starts_with(atom)) || NUMBER_PREFIX.is_match(atom)
+    }
+}
+
+static JSON_NUMBER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^-?(0|[1-9]\d*)(\.\d+)?([eE][+-]?\d+)?$").unwrap());
+static NUMBER_PREFIX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^-?(\d+(\.\d*)?([eE][+-]?\d*)?)?$").unwrap());
+
+/// Python中尚未确定是元组还是括号的`(`
+#[derive(Debug, Clone, Copy)]
+struct Paren {
+    src_idx: usize,
+    // 元组的`[`在翻译结果中的位置，以及之后的第一个token在`boundaries`中的下标
+    out_idx: usize,
+    boundary: usize,
+    // `(`在`stack`中占据的层数
+    depth: usize,
+    content: bool,
+}
+
+/// Python的字符串前缀，例如`r`、`b`、`rb`
+pub(crate) fn is_string_prefix(atom: &str) -> bool {
+    matches!(
+        atom.to_ascii_lowercase().as_str(),
+        "r" | "u" | "b" | "f" | "br" | "rb" | "fr" | "rf"
+    )
+}
+
+/// 残缺的`True`/`False`/`None`翻译为json关键字相同长度的前缀，之后由关键字的前缀补全处理
+fn translate_python(atom: &str, complete: bool) -> String {
+    for (keyword, json) in [("True", "true"), ("False", "false"), ("None", "null")] {
+        if !atom.is_empty() && keyword.starts_with(atom) {
+            return json[..atom.len()].to_string();
+        }
+    }
+    // 数字中可以出现下划线
+    translate_number(&atom.replace('_', ""), complete)
+}
+
+/// JSON5以及Python中的数字：十六进制等其它进制、开头的`+`、省略整数或小数部分，以及带符号的NaN
+fn translate_number(atom: &str, complete: bool) -> String {
+    let (sign, body) = match atom.strip_prefix(['+', '-']) {
+        Some(body) => (if atom.starts_with('-') { "-" } else { "" }, body),
+        None => ("", atom),
+    };
+    if "NaN".starts_with(body) && !body.is_empty() {
+        // NaN没有符号
+        return body.to_string();
+    }
+    let radix = match body.get(..2) {
+        Some("0x" | "0X") => 16,
+        Some("0o" | "0O") => 8,
+        Some("0b" | "0B") => 2,
+        _ => 10,
+    };
+    if radix != 10 {
+        let digits = &body[2..];
+        if digits.is_empty() {
+            return if complete {
+                atom.to_string()
+            } else {
+                String::new()
+            };
+        }
+        if !digits.chars().all(|c| c.is_digit(radix)) {
+            return atom.to_string();
+        }
+        return match u128::from_str_radix(digits, radix) {
+            Ok(num) => format!("{}{}", sign, num),
+            Err(_) => {
+                let num = digits.chars().fold(0f64, |acc, c| {
+                    acc * radix as f64 + c.to_digit(radix).unwrap_or(0) as f64
+                });
+                format!("{}{:e}", sign, num)
+            }
+        };
+    }
+    if !body.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+        return format!("{}{}", sign, body);
+    }
+    let mut num = String::from(sign);
+    if body.starts_with('.') {
+        num.push('0');
+    }
+    num.push_str(body);
+    if complete {
+        // `5.`以及`5.e3`这样小数点之后没有数字的写法
+        if num.ends_with('.') {
+            num.push('0');
+        }
+        num = num.replace(".e", ".0e").replace(".E", ".0E");
+    }
+    num
+}
+
+/// 方言输入的原文以及翻译器
+#[derive(Debug, Clone)]
+pub(crate) struct DialectInput {
+    source: String,
+    translator: Translator,
+}
+
+impl DialectInput {
+    pub fn new(dialect: Dialect) -> DialectInput {
+        DialectInput {
+            source: String::new(),
+            translator: Translator::new(dialect),
+        }
+    }
+
+    pub fn push(&mut self, chunk: &str) -> String {
+        self.source.push_str(chunk);
+        self.translator.push(chunk)
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn tail(&self) -> String {
+        self.translator.tail()
+    }
+
+    pub fn to_source(&self, text: &str, completed: &str) -> String {
+        self.translator.to_source(&self.source, text, completed)
+    }
+
+    pub fn source_offset(&self, offset: usize) -> usize {
+        self.translator.source_offset(&self.source, offset)
+    }
+
+    /// 翻译结果中从`start`开始、在`end`处的逗号或者括号之前结束的值在输入中的范围，
+    /// 不包括值之后的空白和注释
+    pub fn source_range(&self, start: usize, end: usize) -> Range<usize> {
+        let mut start = self.source_offset(start);
+        let end = self.source_offset(end).max(start);
+        // 翻译结果中的字符串从引号开始，Python字符串的前缀需要包括在内
+        if self.translator.dialect == Dialect::PythonLiteral {
+            let head = &self.source[..start];
+            let prefix = head.len()
+                - head
+                    .trim_end_matches(|c: char| c.is_ascii_alphabetic())
+                    .len();
+            if prefix > 0 && is_string_prefix(&head[start - prefix..]) {
+                start -= prefix;
+            }
+        }
+        let value_end = find_value_end(&self.source, start, self.translator.dialect)
+            .map_or(end, |value_end| value_end.min(end));
+        start..start + self.source[start..value_end].trim_end().len()
+    }
+
+    pub fn error(&self) -> Option<usize> {
+        self.translator.error()
+    }
+
+    pub fn committed_error(&self) -> Option<usize> {
+        self.translator.committed_error()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Dialect, Translator};
+
+    fn translate(s: &str) -> String {
+        let mut translator = Translator::new(Dialect::Json5);
+        translator.push(s) + &translator.tail()
+    }
+
+    #[test]
+    fn test_translate_json5() {
+        let cases = [
+            ("{a: 1, 'b': 'x\"y', }", r#"{"a": 1, "b": "x\"y" }"#),
+            (
+                "[0x1F, +1, .5, 5., -.5e3, +Infinity, -NaN,]",
+                "[31, 1, 0.5, 5.0, -0.5e3, Infinity, NaN]",
+            ),
+            ("// c\n[1, /* c */ 2]", "\n[1,   2]"),
+            ("'a\\\nb\\x41\\'\\v'", r#""ab\u0041'\u000b""#),
+            ("{$key_1: [", r#"{"$key_1": ["#),
+            ("{ab", r#"{"ab"#),
+            ("['x\\u00", r#"["x"#),
+            ("[0x", "["),
+            ("[1, /", "[1, "),
+            // json中不允许的空白换成空格
+            ("{a:\u{a0}1}", r#"{"a": 1}"#),
+            ("[\u{feff}1]", "[ 1]"),
+            ("{a: 'x'\u{2028}, b: 2}", r#"{"a": "x" , "b": 2}"#),
+            // 字符串中的控制字符
+            ("['a\tb', '\u{1}\u{8}'", r#"["a\tb", "\u0001\b""#),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(translate(input), expected, "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_translate_python() {
+        let cases = [
+            ("{'a': True, 1: None", r#"{"a": true, "1": null"#),
+            ("(rb'\\n\"', u\"x\")", r#"["\\n\"", "x"]"#),
+            ("['\\0\\x7f\\e', Fal", r#"["\u0000\u007f\\e", fal"#),
+            ("[0o17, 0b11, 1_0.5, r", "[15, 3, 10.5, "),
+            // 出现逗号之后才是元组
+            ("[(1), ((2, 3)), (4,), (", "[1, [2, 3], [4], "),
+            ("'''a'b\"\n''' \"\"\"c", r#""a'b\"\n" "c"#),
+            (r"[r'\\', r'\'', '''x''", r#"["\\\\", "\\'", "x"#),
+            ("['a\tb\x01', '''\u{1}", r#"["a\tb\u0001", "\u0001"#),
+        ];
+        for (input, expected) in cases {
+            let mut translator = Translator::new(Dialect::PythonLiteral);
+            let res = translator.push(input) + &translator.tail();
+            assert_eq!(res, expected, "input: {:?}", input);
+            assert_eq!(translator.error(), None);
+        }
+
+        // 不支持的字面量，以及永远不会合法的残缺字面量
+        let cases = [
+            ("[1e5, -0.5, 1j]", 12),
+            ("['a' // 2", 5),
+            ("[1, abc", 4),
+            // 不带引号的key只有JSON5中才有
+            ("{'a': 1, abc: 2}", 9),
+            ("{1j: 2}", 1),
+            // 作为key的元组以及集合
+            ("{1: 2, (1,2): 3}", 9),
+            ("{'a': {1, 2}}", 10),
+            ("{'a' 1}", 5),
+        ];
+        for (input, offset) in cases {
+            let mut translator = Translator::new(Dialect::PythonLiteral);
+            translator.push(input);
+            assert_eq!(translator.error(), Some(offset), "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_translate_incremental() {
+        let input = "{a: [0x10, 'x\\u0041y', .5,], /* c */ b: +1}";
+        let expected = translate(input);
+        for chunk_len in 1..6 {
+            let mut translator = Translator::new(Dialect::Json5);
+            let mut res = String::new();
+            for chunk in input.as_bytes().chunks(chunk_len) {
+                res.push_str(&translator.push(std::str::from_utf8(chunk).unwrap()));
+            }
+            res.push_str(&translator.tail());
+            assert_eq!(res, expected);
+        }
+
+        let input = r#"{'a': ((1), '''x'y''', (2,)), 'b': (r'\'', """q""", ())}"#;
+        let mut translator = Translator::new(Dialect::PythonLiteral);
+        let expected = translator.push(input) + &translator.tail();
+        for chunk_len in 1..6 {
+            let mut translator = Translator::new(Dialect::PythonLiteral);
+            let mut res = String::new();
+            for chunk in input.as_bytes().chunks(chunk_len) {
+                res.push_str(&translator.push(std::str::from_utf8(chunk).unwrap()));
+            }
+            res.push_str(&translator.tail());
+            assert_eq!(res, expected);
+        }
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&expected).unwrap(),
+            serde_json::json!({"a": [1, "x'y", [2]], "b": ["\\'", "q", []]})
+        );
+    }
+
+    /// 尚未确定是元组还是括号的`(`之后的输入同样只翻译一次：
+    /// 每次追加时只有上一次残留的未结束token需要重新扫描
+    #[test]
+    fn test_translate_paren_incremental() {
+        let input = "({'a': [".to_string() + &"1, ".repeat(5_000);
+        let mut translator = Translator::new(Dialect::PythonLiteral);
+        let mut res = String::new();
+        for chunk in input.as_bytes().chunks(16) {
+            res.push_str(&translator.push(std::str::from_utf8(chunk).unwrap()));
+            assert!(translator.pending.len() < 4, "{:?}", translator.pending);
+        }
+        // 翻译结果留在`deferred`中，直到`(`被确定
+        assert_eq!(res, "");
+        res.push_str(&translator.tail());
+        assert!(res.starts_with(r#"{"a": [1, 1"#) && res.ends_with("1, "));
+    }
+}
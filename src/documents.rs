@@ -0,0 +1,271 @@
+use std::ops::Range;
+
+use crate::dialect::is_string_prefix;
+use crate::json_lines::check_complete;
+use crate::parser::{ParseSettings, Parser};
+use crate::value_parser::is_space;
+use crate::{Dialect, Error};
+
+/// 多个顶层值连在一起时，返回哪些值
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentPolicy {
+    First,
+    #[default]
+    Last,
+    All,
+}
+
+/// 输入中的一个顶层值
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Document {
+    /// 在输入中的字节范围
+    pub range: Range<usize>,
+    /// 完整的值原样返回，最后一个残缺的值返回补全后的结果，
+    /// 方言输入时两者都是严格的json，设置了`keep_dialect`的JSON5除外。
+    /// 错误中的offset是相对于这个值的
+    pub result: Result<String, Error>,
+}
+
+/// 依次返回`{...}{...}`、`[1] [2]`或者`1 2 3`这样连在一起的顶层值，
+/// 只有最后一个值会被补全。
+pub fn documents(in_str: &str, settings: impl Into<ParseSettings>) -> Documents<'_> {
+    Documents {
+        src: in_str,
+        pos: 0,
+        settings: settings.into(),
+    }
+}
+
+/// 按照`policy`补全连在一起的多个顶层值，任何一个需要返回的值出错时都返回错误
+pub fn parse_documents(
+    in_str: &str,
+    settings: impl Into<ParseSettings>,
+    policy: DocumentPolicy,
+) -> Result<Vec<String>, Error> {
+    let mut docs = documents(in_str, settings);
+    let res = match policy {
+        DocumentPolicy::First => docs.next().map(|doc| vec![doc.result]),
+        DocumentPolicy::Last => docs.last().map(|doc| vec![doc.result]),
+        DocumentPolicy::All => Some(docs.map(|doc| doc.result).collect()),
+    };
+    match res {
+        Some(res) if !res.is_empty() => res.into_iter().collect(),
+        _ => Err(Error::Empty),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Documents<'a> {
+    src: &'a str,
+    pos: usize,
+    settings: ParseSettings,
+}
+
+/// 从`start`开始的顶层值的结束位置，值没有结束时返回`None`。
+/// 按照方言识别单引号字符串、JSON5的注释以及Python的元组、带前缀的字符串和三引号字符串
+pub(crate) fn find_value_end(src: &str, start: usize, dialect: Dialect) -> Option<usize> {
+    let python = dialect == Dialect::PythonLiteral;
+    let is_quote = |c: char| c == '"' || (dialect != Dialect::Json && c == '\'');
+    let is_open = |c: char| matches!(c, '{' | '[') || (python && c == '(');
+    let is_close = |c: char| matches!(c, '}' | ']') || (python && c == ')');
+    let rest = &src[start..];
+    let first = rest.chars().next()?;
+    // Python字符串的前缀，例如`r'...'`
+    let prefix = rest
+        .find(is_quote)
+        .filter(|&n| n > 0 && python && is_string_prefix(&rest[..n]))
+        .unwrap_or(0);
+    let is_atom = prefix == 0 && !is_open(first) && !is_quote(first);
+    let mut depth = 0usize;
+    // 所在字符串的引号，Python的三引号字符串以三个引号结束
+    let mut quote: Option<&str> = None;
+    let mut escaped = false;
+    let mut idx = start + prefix;
+    while let Some(c) = src[idx..].chars().next() {
+        let next = src[idx + c.len_utf8()..].chars().next();
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quote.is_some() => escaped = true,
+            _ if quote.is_some_and(|q| src[idx..].starts_with(q)) => {
+                idx += quote.take().map_or(1, str::len);
+                if depth == 0 {
+                    return Some(idx);
+                }
+                continue;
+            }
+            _ if quote.is_some() => {}
+            '/' if dialect == Dialect::Json5 && matches!(next, Some('/' | '*')) => {
+                if is_atom && idx > start {
+                    return Some(idx);
+                }
+                // 跳过注释，块注释没有结束时值也没有结束
+                idx = comment_end(src, idx)?;
+                continue;
+            }
+            // 原子值在空白或者下一个值开始的地方结束
+            _ if is_atom && (is_space(c) || is_open(c) || is_quote(c)) => return Some(idx),
+            _ if is_quote(c) => {
+                let triple = if c == '"' { "\"\"\"" } else { "'''" };
+                let q = if python && src[idx..].starts_with(triple) {
+                    triple
+                } else {
+                    &triple[..1]
+                };
+                quote = Some(q);
+                idx += q.len();
+                continue;
+            }
+            _ if is_open(c) => depth += 1,
+            _ if is_close(c) => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    return Some(idx + 1);
+                }
+            }
+            _ => {}
+        }
+        idx += c.len_utf8();
+    }
+    None
+}
+
+/// 从`idx`处的`//`或者`/*`开始的注释的结束位置
+fn comment_end(src: &str, idx: usize) -> Option<usize> {
+    if src[idx..].starts_with("//") {
+        Some(src[idx..].find('\n').map_or(src.len(), |n| idx + n + 1))
+    } else {
+        src[idx + 2..].find("*/").map(|n| idx + 2 + n + 2)
+    }
+}
+
+/// 跳过`pos`之后的空白，JSON5还会跳过值之间的注释
+pub(crate) fn skip_blank(src: &str, mut pos: usize, dialect: Dialect) -> usize {
+    loop {
+        pos = src.len() - src[pos..].trim_start_matches(is_space).len();
+        let rest = &src[pos..];
+        if dialect != Dialect::Json5 || !(rest.starts_with("//") || rest.starts_with("/*")) {
+            return pos;
+        }
+        match comment_end(src, pos) {
+            Some(end) => pos = end,
+            // 没有结束的块注释交给补全处理
+            None => return pos,
+        }
+    }
+}
+
+impl Iterator for Documents<'_> {
+    type Item = Document;
+
+    fn next(&mut self) -> Option<Document> {
+        let dialect = self.settings.dialect;
+        let start = skip_blank(self.src, self.pos, dialect);
+        if start == self.src.len() {
+            return None;
+        }
+        let (end, result) = match find_value_end(self.src, start, dialect) {
+            Some(end) if end < self.src.len() => {
+                (end, check_complete(&self.src[start..end], &self.settings))
+            }
+            // 最后一个值需要补全
+            _ => {
+                let end = self.src.len();
+                let res = Parser::parser_with(&self.src[start..], self.settings.clone());
+                (end, res)
+            }
+        };
+        self.pos = end;
+        Some(Document {
+            range: start..end,
+            result,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{documents, parse_documents, DocumentPolicy};
+    use crate::{Allow, Dialect, Error, NonFinitePolicy, ParseSettings};
+
+    #[test]
+    fn test_documents() {
+        let src = r#"{"a": 1}{"b": [2]} "x y" 12 [3, "#;
+        let docs: Vec<_> = documents(src, Allow::ALL).collect();
+        let results: Vec<_> = docs.iter().map(|doc| doc.result.as_deref()).collect();
+        assert_eq!(
+            results,
+            [
+                Ok(r#"{"a": 1}"#),
+                Ok(r#"{"b": [2]}"#),
+                Ok(r#""x y""#),
+                Ok("12"),
+                Ok("[3]"),
+            ]
+        );
+        assert_eq!(docs[1].range, 8..18);
+
+        let src = "[1] [2] [3";
+        let first = parse_documents(src, Allow::ALL, DocumentPolicy::First);
+        assert_eq!(first.unwrap(), ["[1]"]);
+        let last = parse_documents(src, Allow::ALL, DocumentPolicy::Last);
+        assert_eq!(last.unwrap(), ["[3]"]);
+        let all = parse_documents(src, Allow::ALL, DocumentPolicy::All);
+        assert_eq!(all.unwrap(), ["[1]", "[2]", "[3]"]);
+
+        // 一个值出错不影响后面的值
+        let docs: Vec<_> = documents("[1}[2", Allow::ALL).collect();
+        assert!(docs[0].result.is_err());
+        assert_eq!(docs[1].result.as_deref(), Ok("[2]"));
+        assert_eq!(
+            parse_documents(" ", Allow::ALL, DocumentPolicy::All),
+            Err(Error::Empty)
+        );
+
+        // 完整的值与补全的设置无关
+        let results: Vec<_> = documents("1 2 [3", ParseSettings::default())
+            .map(|doc| doc.result)
+            .collect();
+        assert_eq!(results, [Ok("1".into()), Ok("2".into()), Ok("[]".into())]);
+        let error = ParseSettings::new()
+            .allow(Allow::ALL)
+            .non_finite(NonFinitePolicy::Error);
+        let results: Vec<_> = documents("[NaN] [1", error).map(|doc| doc.result).collect();
+        assert_eq!(
+            results,
+            [Err(Error::NonFinite { offset: 1 }), Ok("[1]".into())]
+        );
+    }
+
+    #[test]
+    fn test_documents_dialect() {
+        let json5 = ParseSettings::new()
+            .allow(Allow::ALL)
+            .dialect(Dialect::Json5);
+        let src = "{a: '}]'} // [x\n/* { */ 'y z' 0x10 [1, '";
+        let docs: Vec<_> = documents(src, json5).collect();
+        let results: Vec<_> = docs.iter().map(|doc| doc.result.as_deref()).collect();
+        assert_eq!(
+            results,
+            [
+                Ok(r#"{"a": "}]"}"#),
+                Ok(r#""y z""#),
+                Ok("16"),
+                Ok(r#"[1,""]"#)
+            ]
+        );
+        assert_eq!(docs[1].range, 24..29);
+
+        let python = ParseSettings::new()
+            .allow(Allow::ALL)
+            .dialect(Dialect::PythonLiteral);
+        let all = parse_documents(
+            r"('a]', 1) r'a\'b' '''x'y''' [",
+            python,
+            DocumentPolicy::All,
+        );
+        assert_eq!(
+            all.unwrap(),
+            [r#"["a]", 1]"#, r#""a\\'b""#, r#""x'y""#, "[]"]
+        );
+    }
+}
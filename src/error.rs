@@ -0,0 +1,71 @@
+use std::fmt;
+
+/// 解析以及补全过程中的错误，其中的offset都是输入中的字节序
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// 输入为空
+    Empty,
+    /// 右括号与栈顶的左括号不匹配，栈为空时`expected`为`None`
+    MismatchedBracket {
+        offset: usize,
+        found: char,
+        expected: Option<char>,
+    },
+    /// 从`offset`开始的内容无法补全为合法的json
+    Uncompletable { offset: usize },
+    /// 字符串中`offset`处的转义序列不合法
+    InvalidEscape { offset: usize },
+    /// 文本中没有找到json
+    NotFound,
+    /// 按照[`crate::NonFinitePolicy::Error`]，补全结果中`offset`处出现了`NaN`、`Infinity`或`-Infinity`
+    NonFinite { offset: usize },
+}
+
+impl Error {
+    /// 用`f`转换错误中的offset
+    pub(crate) fn map_offset(self, f: impl Fn(usize) -> usize) -> Error {
+        match self {
+            Self::MismatchedBracket {
+                offset,
+                found,
+                expected,
+            } => Self::MismatchedBracket {
+                offset: f(offset),
+                found,
+                expected,
+            },
+            Self::Uncompletable { offset } => Self::Uncompletable { offset: f(offset) },
+            Self::InvalidEscape { offset } => Self::InvalidEscape { offset: f(offset) },
+            Self::NonFinite { offset } => Self::NonFinite { offset: f(offset) },
+            Self::Empty | Self::NotFound => self,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "input str is empty"),
+            Self::MismatchedBracket {
+                offset,
+                found,
+                expected: Some(expected),
+            } => write!(
+                f,
+                "mismatched bracket at {}: found '{}', expected '{}'",
+                offset, found, expected
+            ),
+            Self::MismatchedBracket {
+                offset,
+                found,
+                expected: None,
+            } => write!(f, "unexpected bracket '{}' at {}", found, offset),
+            Self::Uncompletable { offset } => write!(f, "can not complete input at {}", offset),
+            Self::InvalidEscape { offset } => write!(f, "invalid escape at {}", offset),
+            Self::NotFound => write!(f, "no json found in text"),
+            Self::NonFinite { offset } => write!(f, "non-finite number at {}", offset),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
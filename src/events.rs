@@ -0,0 +1,529 @@
+use serde_json::Value;
+
+use crate::parser::{CharType, ParseSettings, Parser, ScanVisitor};
+use crate::pointer::JsonPath;
+use crate::utils::RunState;
+use crate::value::BuildValue;
+use crate::value_parser::{is_space, unescape_string};
+use crate::Error;
+
+/// [`EventReader`]产生的事件，其中的`path`都是 JSON Pointer
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    StartObject {
+        path: String,
+    },
+    EndObject {
+        path: String,
+    },
+    StartArray {
+        path: String,
+    },
+    EndArray {
+        path: String,
+    },
+    /// 读到了完整的key，`path`是这个key对应的值的位置
+    Key {
+        path: String,
+    },
+    /// 字符串值中新到达的部分，已经去掉了转义，全部拼接起来就是完整的字符串
+    StringChunk {
+        path: String,
+        chunk: String,
+    },
+    /// 原子值已经结束，数组和对象的结束只会产生`EndArray`以及`EndObject`
+    ValueComplete {
+        path: String,
+        value: Value,
+    },
+}
+
+/// 事件风格的增量解析器，与[`StreamingParser`](crate::StreamingParser)共用同一个状态机。
+///
+/// 每个值在结束时立即产生事件，不需要等待外层的数组或对象闭合；
+/// 数字等没有结束符号的原子值要等到后面的逗号或括号到达之后才算结束，
+/// 根节点的原子值则要调用[`EventReader::finish`]。
+#[derive(Debug)]
+pub struct EventReader {
+    parser: Parser<'static>,
+    collector: EventCollector,
+}
+
+impl Default for EventReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventReader {
+    pub fn new() -> EventReader {
+        Self::with_settings(ParseSettings::default())
+    }
+
+    pub fn with_settings(settings: impl Into<ParseSettings>) -> EventReader {
+        let settings = settings.into();
+        let mut parser = Parser::parser_owned(settings.clone());
+        parser.parse();
+        EventReader {
+            parser,
+            collector: EventCollector {
+                settings,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// 追加输入，返回由这部分输入产生的事件。
+    /// 出错时先返回出错之前已经产生的事件，之后的调用再返回错误
+    pub fn feed(&mut self, chunk: &str) -> Result<Vec<Event>, Error> {
+        if self.error().is_none() {
+            self.parser.push_str_with(chunk, &mut self.collector);
+        }
+        if self.error().is_none() {
+            self.collector.flush(self.parser.src_str());
+        }
+        self.take_events()
+    }
+
+    /// 输入已经结束，返回根节点的原子值等只有在输入结束时才能确定的事件，
+    /// 例如`123`；根节点还没有结束时返回错误
+    pub fn finish(&mut self) -> Result<Vec<Event>, Error> {
+        if self.error().is_none() {
+            let src = self.parser.src_str().to_string() + &self.parser.dialect_tail();
+            let trace = self.parser.parse_tracer();
+            let err = if let Some(err) = self.parser.dialect_error() {
+                Some(err)
+            } else if src.trim_matches(is_space).is_empty() {
+                Some(Error::Empty)
+            } else if !trace.stack.is_empty() || self.collector.string.is_some() {
+                Some(Error::Uncompletable { offset: src.len() })
+            } else {
+                None
+            };
+            match err {
+                Some(err) => self.collector.error = Some(err),
+                None => self.collector.finish(&src),
+            }
+        }
+        self.take_events()
+    }
+
+    fn error(&self) -> Option<Error> {
+        let err = match self.parser.is_parsed() {
+            RunState::Error(err) => Some(err.clone()),
+            _ => self.collector.error.clone(),
+        };
+        err.map(|err| self.parser.source_error(err))
+    }
+
+    fn take_events(&mut self) -> Result<Vec<Event>, Error> {
+        let events = std::mem::take(&mut self.collector.events);
+        match self.error() {
+            Some(err) if events.is_empty() => Err(err),
+            _ => Ok(events),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct EventCollector {
+    settings: ParseSettings,
+    path: JsonPath,
+    // 上一个结构字符之后的位置
+    last: usize,
+    // 尚未结束的字符串
+    string: Option<OpenString>,
+    // 上一个字符串已经处理完毕，下一个结构字符之前的内容不再视为原子值
+    string_done: bool,
+    events: Vec<Event>,
+    error: Option<Error>,
+}
+
+#[derive(Debug)]
+struct OpenString {
+    // 开头的引号的位置
+    start: usize,
+    // 已经去掉转义的部分在输入中的结束位置，总是位于转义序列的边界上
+    decoded_to: usize,
+    // 已经去掉转义的内容，也就是已经作为`StringChunk`发出的内容
+    text: String,
+}
+
+/// 去掉转义，宽松模式下非法转义的反斜杠按照普通字符处理
+fn decode(settings: &ParseSettings, raw: &str) -> Option<String> {
+    unescape_string(raw).or_else(|| {
+        let mut parser = Parser::with_settings(raw, settings.clone());
+        parser.parse();
+        parser.repaired_src().and_then(|s| unescape_string(&s))
+    })
+}
+
+impl EventCollector {
+    /// 每次输入结束时，把尚未结束的字符串中新确定的部分作为`StringChunk`发出，
+    /// 只处理上一次之后新到达的内容
+    fn flush(&mut self, src: &str) {
+        if self.path.is_key() {
+            return;
+        }
+        let Some(open) = &mut self.string else {
+            return;
+        };
+        let end = stable_end(src, open.decoded_to);
+        if end == open.decoded_to {
+            return;
+        }
+        let raw = format!("\"{}\"", &src[open.decoded_to..end]);
+        if let Some(chunk) = decode(&self.settings, &raw) {
+            open.decoded_to = end;
+            open.text.push_str(&chunk);
+            self.events.push(Event::StringChunk {
+                path: self.path.pointer(),
+                chunk,
+            });
+        }
+    }
+
+    /// 输入结束时，处理根节点末尾的原子值
+    fn finish(&mut self, src: &str) {
+        let raw = src[self.last..].trim_end_matches(is_space);
+        let atom = raw.trim_start_matches(is_space);
+        let offset = self.last + raw.len() - atom.len();
+        self.last = src.len();
+        if std::mem::take(&mut self.string_done) || atom.is_empty() {
+            return;
+        }
+        match self.atom(atom, offset) {
+            Ok(value) => self.events.push(Event::ValueComplete {
+                path: self.path.pointer(),
+                value,
+            }),
+            Err(err) => self.error = Some(err),
+        }
+    }
+
+    /// 结束的原子值，`NaN`等按照`non_finite`的设置处理
+    fn atom(&self, atom: &str, offset: usize) -> Result<Value, Error> {
+        let atom = self.settings.non_finite.apply(atom, offset)?;
+        Value::atom(&atom).ok_or(Error::Uncompletable { offset })
+    }
+}
+
+/// 从`from`开始，残缺字符串中已经确定的部分的结束位置，不包括未完成的转义，
+/// 以及可能与后面的`\uDCxx`组成代理对的`\uD8xx`
+fn stable_end(src: &str, from: usize) -> usize {
+    let bytes = src.as_bytes();
+    let mut idx = from;
+    while idx < bytes.len() {
+        if bytes[idx] != b'\\' {
+            idx += 1;
+            continue;
+        }
+        let len = if bytes.get(idx + 1) == Some(&b'u') {
+            6
+        } else {
+            2
+        };
+        let is_high_surrogate = len == 6
+            && matches!(bytes.get(idx + 2), Some(b'd' | b'D'))
+            && matches!(
+                bytes.get(idx + 3),
+                Some(b'8'..=b'9' | b'a'..=b'b' | b'A'..=b'B')
+            );
+        if idx + len > bytes.len() || (is_high_surrogate && idx + 2 * len > bytes.len()) {
+            break;
+        }
+        idx += len;
+    }
+    idx.min(src.len())
+}
+
+impl ScanVisitor for EventCollector {
+    fn visit(&mut self, src: &str, idx: usize, char_type: CharType) {
+        if self.error.is_some() {
+            return;
+        }
+        let raw = src[self.last..idx].trim_end_matches(is_space);
+        let atom = raw.trim_start_matches(is_space);
+        let offset = self.last + raw.len() - atom.len();
+        self.last = idx + 1;
+        // 字符串以及key已经在引号结束时处理过了
+        let handled = std::mem::take(&mut self.string_done) || char_type == CharType::Colon;
+        if !handled && !atom.is_empty() {
+            match self.atom(atom, offset) {
+                Ok(value) => self.events.push(Event::ValueComplete {
+                    path: self.path.pointer(),
+                    value,
+                }),
+                Err(err) => {
+                    self.error = Some(err);
+                    return;
+                }
+            }
+        }
+
+        match char_type {
+            CharType::LFB => {
+                self.events.push(Event::StartArray {
+                    path: self.path.pointer(),
+                });
+                self.path.enter_array();
+            }
+            CharType::LCB => {
+                self.events.push(Event::StartObject {
+                    path: self.path.pointer(),
+                });
+                self.path.enter_object();
+            }
+            CharType::RFB => {
+                self.path.leave();
+                self.events.push(Event::EndArray {
+                    path: self.path.pointer(),
+                });
+            }
+            CharType::RCB => {
+                self.path.leave();
+                self.events.push(Event::EndObject {
+                    path: self.path.pointer(),
+                });
+            }
+            CharType::Comma => self.path.next(),
+            _ => {}
+        }
+    }
+
+    fn visit_quote(&mut self, src: &str, idx: usize) {
+        let Some(open) = self.string.take() else {
+            self.string = Some(OpenString {
+                start: idx,
+                decoded_to: idx + 1,
+                text: String::new(),
+            });
+            return;
+        };
+        self.string_done = true;
+        let raw = format!("\"{}\"", &src[open.decoded_to..idx]);
+        let Some(chunk) = decode(&self.settings, &raw) else {
+            self.error = Some(Error::InvalidEscape { offset: open.start });
+            return;
+        };
+        let mut text = open.text;
+        text.push_str(&chunk);
+        if self.path.is_key() {
+            self.path.set_key(Some(text));
+            self.events.push(Event::Key {
+                path: self.path.pointer(),
+            });
+            return;
+        }
+        let path = self.path.pointer();
+        if !chunk.is_empty() {
+            self.events.push(Event::StringChunk {
+                path: path.clone(),
+                chunk,
+            });
+        }
+        self.events.push(Event::ValueComplete {
+            path,
+            value: Value::String(text),
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Event, EventReader};
+    use crate::test_utils::{arb_json, normalize};
+    use crate::{Dialect, Error, NonFinitePolicy, ParseSettings};
+    use proptest::prelude::*;
+    use serde_json::{json, Value};
+
+    fn complete_value(events: &[Event]) -> Option<Value> {
+        events.iter().find_map(|e| match e {
+            Event::ValueComplete { value, .. } => Some(value.clone()),
+            _ => None,
+        })
+    }
+
+    fn chunks(events: &[Event]) -> String {
+        events
+            .iter()
+            .filter_map(|e| match e {
+                Event::StringChunk { chunk, .. } => Some(chunk.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_events() {
+        let mut reader = EventReader::new();
+        let events = reader.feed(r#"{"a": [1, "x\u00"#).unwrap();
+        assert_eq!(
+            events,
+            [
+                Event::StartObject { path: "".into() },
+                Event::Key { path: "/a".into() },
+                Event::StartArray { path: "/a".into() },
+                Event::ValueComplete {
+                    path: "/a/0".into(),
+                    value: json!(1),
+                },
+                Event::StringChunk {
+                    path: "/a/1".into(),
+                    chunk: "x".into(),
+                },
+            ]
+        );
+        let events = reader.feed(r#"e9"], "b": tr"#).unwrap();
+        assert_eq!(
+            events,
+            [
+                Event::StringChunk {
+                    path: "/a/1".into(),
+                    chunk: "é".into(),
+                },
+                Event::ValueComplete {
+                    path: "/a/1".into(),
+                    value: json!("xé"),
+                },
+                Event::EndArray { path: "/a".into() },
+                Event::Key { path: "/b".into() },
+            ]
+        );
+        let events = reader.feed("ue}").unwrap();
+        assert_eq!(
+            events,
+            [
+                Event::ValueComplete {
+                    path: "/b".into(),
+                    value: json!(true),
+                },
+                Event::EndObject { path: "".into() },
+            ]
+        );
+        assert!(reader.feed("]").is_err());
+    }
+
+    #[test]
+    fn test_finish() {
+        let mut reader = EventReader::new();
+        assert_eq!(reader.feed("123").unwrap(), []);
+        let events = reader.finish().unwrap();
+        assert_eq!(
+            events,
+            [Event::ValueComplete {
+                path: "".into(),
+                value: json!(123),
+            }]
+        );
+        assert_eq!(reader.finish().unwrap(), []);
+
+        let mut reader = EventReader::with_settings(ParseSettings::new().dialect(Dialect::Json5));
+        reader.feed(" 0x10 ").unwrap();
+        assert_eq!(complete_value(&reader.finish().unwrap()), Some(json!(16)));
+
+        let mut reader = EventReader::new();
+        reader.feed("[1, 2").unwrap();
+        assert_eq!(reader.finish(), Err(Error::Uncompletable { offset: 5 }));
+        assert_eq!(EventReader::new().finish(), Err(Error::Empty));
+    }
+
+    #[test]
+    fn test_error_keeps_events() {
+        // 出错之前的事件先返回，错误在下一次调用时返回
+        let mut reader = EventReader::new();
+        let events = reader.feed("[1, [2]}").unwrap();
+        assert_eq!(events.len(), 5);
+        assert_eq!(
+            reader.feed("3"),
+            Err(Error::MismatchedBracket {
+                offset: 7,
+                found: '}',
+                expected: Some(']'),
+            })
+        );
+        assert!(reader.finish().is_err());
+    }
+
+    #[test]
+    fn test_non_finite() {
+        let values = |policy: NonFinitePolicy| {
+            let mut reader = EventReader::with_settings(ParseSettings::new().non_finite(policy));
+            let mut events = reader.feed("[NaN, 1, -Infinity]").unwrap();
+            events.extend(reader.finish().unwrap());
+            events
+                .into_iter()
+                .filter_map(|e| match e {
+                    Event::ValueComplete { value, .. } => Some(value),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            values(NonFinitePolicy::Preserve),
+            [json!(null), json!(1), json!(null)]
+        );
+        assert_eq!(
+            values(NonFinitePolicy::Null),
+            [json!(null), json!(1), json!(null)]
+        );
+        assert_eq!(
+            values(NonFinitePolicy::String),
+            [json!("NaN"), json!(1), json!("-Infinity")]
+        );
+
+        let settings = ParseSettings::new().non_finite(NonFinitePolicy::Error);
+        let mut reader = EventReader::with_settings(settings.clone());
+        let events = reader.feed("[NaN, 1]").unwrap();
+        assert_eq!(events, [Event::StartArray { path: "".into() }]);
+        assert_eq!(reader.finish(), Err(Error::NonFinite { offset: 1 }));
+
+        // 根节点的原子值在输入结束时处理
+        let mut reader = EventReader::with_settings(settings);
+        reader.feed(" Infinity").unwrap();
+        assert_eq!(reader.finish(), Err(Error::NonFinite { offset: 1 }));
+    }
+
+    #[test]
+    fn test_surrogate_chunk() {
+        let mut reader = EventReader::new();
+        let mut events = reader.feed(r#"["\ud83d"#).unwrap();
+        assert_eq!(chunks(&events), "");
+        events.extend(reader.feed(r#"\ude00!"#).unwrap());
+        assert_eq!(chunks(&events), "😀!");
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(256))]
+        #[test]
+        fn events_same_as_value(s in arb_json(), chunk_len in 1usize..8) {
+            let s = s.to_string();
+            let mut reader = EventReader::new();
+            let chars: Vec<char> = s.chars().collect();
+            let mut events = Vec::new();
+            for chunk in chars.chunks(chunk_len) {
+                let chunk: String = chunk.iter().collect();
+                events.extend(reader.feed(&chunk).unwrap());
+            }
+            events.extend(reader.finish().unwrap());
+            // 每个结束的值都与完整解析的结果相同，字符串的片段拼接起来就是完整的值
+            let expected = json5::from_str::<Value>(&s).unwrap();
+            for event in &events {
+                let Event::ValueComplete { path, value } = event else {
+                    continue;
+                };
+                prop_assert_eq!(expected.pointer(path).cloned().map(normalize), Some(normalize(value.clone())));
+                if let Value::String(v) = value {
+                    let parts: String = events
+                        .iter()
+                        .filter_map(|e| match e {
+                            Event::StringChunk { path: p, chunk } if p == path => Some(chunk.as_str()),
+                            _ => None,
+                        })
+                        .collect();
+                    prop_assert_eq!(&parts, v);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use std::ops::Range;
+
+use crate::documents::find_value_end;
+use crate::parser::{ParseSettings, Parser};
+use crate::value_parser::is_space;
+use crate::{loads, Dialect, Error};
+
+/// 从文本中提取并补全的json
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Extraction {
+    /// json在原始文本中的字节范围
+    pub span: Range<usize>,
+    pub completed: String,
+}
+
+/// 找到文本中json所在的范围。
+///
+/// 存在` ```json `代码块（不区分大小写，包括` ```jsonc `等），或者在第一个`{`/`[`之前存在
+/// 没有语言标记的代码块时，只在代码块中查找；其它语言的代码块，例如` ```python `，整体被跳过。
+/// 否则从第一个`{`或`[`开始，根节点已经闭合但不是合法的json时，例如`[this]`，
+/// 跳过它从下一个`{`或`[`开始。根节点闭合之后的内容以及代码块的结束标记都会被忽略。
+pub fn find_json(text: &str) -> Option<Range<usize>> {
+    find_json_with(text, Dialect::Json)
+}
+
+/// 与[`find_json`]相同，但是按照`dialect`识别单引号字符串以及注释，
+/// 其中的括号不会结束根节点
+pub fn find_json_with(text: &str, dialect: Dialect) -> Option<Range<usize>> {
+    // 只转换ASCII字符的大小写，位置与原文相同
+    if let Some(fence) = text.to_ascii_lowercase().find("```json") {
+        return fence_content(text, fence, dialect);
+    }
+    let mut from = 0;
+    loop {
+        let first_bracket = text[from..].find(['{', '[']).map(|idx| from + idx);
+        let fence = text[from..]
+            .find("```")
+            .map(|idx| from + idx)
+            .filter(|idx| first_bracket.is_none_or(|b| *idx < b));
+        let Some(fence) = fence else {
+            return find_in_prose(text, first_bracket?, dialect);
+        };
+        // 语言标记所在的行还没有结束时无法判断代码块的语言
+        let line_end = fence + text[fence..].find('\n')?;
+        if text[fence + 3..line_end].trim().is_empty() {
+            return fence_content(text, fence, dialect);
+        }
+        // 其它语言的代码块中的括号不是json
+        from = line_end + text[line_end..].find("```")? + 3;
+    }
+}
+
+/// `fence`处代码块中json的范围
+fn fence_content(text: &str, fence: usize, dialect: Dialect) -> Option<Range<usize>> {
+    // 代码块的内容从下一行开始，这一行还没有结束时说明内容尚未到达
+    let content = fence + text[fence..].find('\n')? + 1;
+    let limit = text[content..]
+        .find("```")
+        .map_or(text.len(), |idx| content + idx);
+    let start = content + text[content..limit].find(|c| !is_space(c))?;
+    // 根节点已经闭合时不受代码块结束标记的影响，例如字符串中的```
+    let end = find_value_end(text, start, dialect).unwrap_or(limit);
+    Some(trimmed(text, start..end))
+}
+
+/// 从`start`处的括号开始依次尝试，跳过已经闭合但是无法解析的括号
+fn find_in_prose(text: &str, mut start: usize, dialect: Dialect) -> Option<Range<usize>> {
+    loop {
+        let Some(end) = find_value_end(text, start, dialect) else {
+            return Some(trimmed(text, start..text.len()));
+        };
+        let span = trimmed(text, start..end);
+        if loads(&text[span.clone()], ParseSettings::new().dialect(dialect)).is_ok() {
+            return Some(span);
+        }
+        start += 1 + text[start + 1..].find(['{', '['])?;
+    }
+}
+
+/// 去掉范围末尾的空白
+fn trimmed(text: &str, span: Range<usize>) -> Range<usize> {
+    span.start..span.start + text[span].trim_end_matches(is_space).len()
+}
+
+/// 提取文本中的json并补全，适用于仍在流式输出的模型回复
+pub fn extract_json(text: &str, settings: impl Into<ParseSettings>) -> Result<Extraction, Error> {
+    let settings = settings.into();
+    let span = find_json_with(text, settings.dialect).ok_or(Error::NotFound)?;
+    let completed = Parser::parser_with(&text[span.clone()], settings)?;
+    Ok(Extraction { span, completed })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{extract_json, find_json, find_json_with};
+    use crate::{Allow, Dialect, Error, ParseSettings};
+
+    #[test]
+    fn test_extract() {
+        let text = "Sure! Here is the data:\n```json\n{\"a\": 1, \"b\": \"x";
+        let res = extract_json(text, Allow::ALL).unwrap();
+        assert_eq!(res.span, 32..text.len());
+        assert_eq!(res.completed, r#"{"a": 1, "b":"x"}"#);
+
+        let text = "```json\n[1, 2]\n```\nHope this helps {ok}";
+        assert_eq!(&text[find_json(text).unwrap()], "[1, 2]");
+        let text = "```\n  42\n```";
+        assert_eq!(&text[find_json(text).unwrap()], "42");
+        let text = "Result: {\"s\": \"``` }\"} and more [text]";
+        assert_eq!(&text[find_json(text).unwrap()], "{\"s\": \"``` }\"}");
+        let text = "```json\n[1, 2\n```";
+        assert_eq!(extract_json(text, Allow::ALL).unwrap().completed, "[1,2]");
+
+        // 已经闭合但不是json的括号被跳过
+        let text = "I think [this] is it: {\"a\": 1";
+        let res = extract_json(text, Allow::ALL).unwrap();
+        assert_eq!(res.span, 22..text.len());
+        assert_eq!(res.completed, r#"{"a":1}"#);
+        let text = "Use {curly} or [1, [x]] then [2]";
+        assert_eq!(&text[find_json(text).unwrap()], "[2]");
+        assert_eq!(find_json("only {words} here"), None);
+
+        // 代码块的语言标记不区分大小写
+        let text = "[note]\n```JSON\n{\"a\": [1";
+        assert_eq!(&text[find_json(text).unwrap()], "{\"a\": [1");
+        let text = "```Jsonc\n[1] // c\n```";
+        assert_eq!(&text[find_json_with(text, Dialect::Json5).unwrap()], "[1]");
+
+        assert_eq!(extract_json("no json", Allow::ALL), Err(Error::NotFound));
+        assert_eq!(extract_json("```js", Allow::ALL), Err(Error::NotFound));
+
+        // 方言中单引号字符串里的括号不会结束根节点
+        let json5 = ParseSettings::new()
+            .allow(Allow::ALL)
+            .dialect(Dialect::Json5);
+        let text = "Here: {a: ']', b: [1, 2";
+        let res = extract_json(text, json5).unwrap();
+        assert_eq!(res.span, 6..text.len());
+        assert_eq!(res.completed, r#"{"a": "]", "b": [1,2]}"#);
+        let text = "```json\n[1, /* ] */ 'x]'] ok";
+        let span = find_json_with(text, Dialect::Json5).unwrap();
+        assert_eq!(&text[span], "[1, /* ] */ 'x]']");
+
+        // 其它语言的代码块被跳过
+        let text = "Here is code:\n```python\nx = 1\n```\nAnd data: {\"a\": 1}";
+        assert_eq!(&text[find_json(text).unwrap()], "{\"a\": 1}");
+        let text = "```rust\nlet v = vec![1];\n```\n```\n[2]\n```";
+        assert_eq!(&text[find_json(text).unwrap()], "[2]");
+        assert_eq!(find_json("```python\nx = [1"), None);
+    }
+}
@@ -0,0 +1,292 @@
+use serde_json::Value;
+
+use crate::documents::{find_value_end, skip_blank};
+use crate::parser::{EscapeMode, NonFinitePolicy, ParseSettings, Parser};
+use crate::value::BuildValue;
+use crate::value_parser::is_space;
+use crate::{Dialect, Error};
+
+/// JSON Lines 中的一条记录
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonLine {
+    /// 记录开始的行号，从1开始
+    pub line: usize,
+    /// 完整的记录原样返回，最后一条残缺的记录返回补全后的结果，
+    /// 方言输入时两者都是严格的json，设置了`keep_dialect`的JSON5除外。
+    /// 错误中的offset是相对于这一行的
+    pub result: Result<String, Error>,
+    /// 这条记录是否经过了补全
+    pub truncated: bool,
+}
+
+/// 按照换行符切分输入，逐条返回记录，空行会被跳过。
+/// JSON5和Python字面量的字符串以及注释可以跨行，其中的换行不会切分记录。
+///
+/// 只有最后一条没有以换行结尾的记录会被补全，前面的记录必须是完整的，
+/// 否则这一条记录返回错误，不影响其它记录。
+pub fn json_lines(in_str: &str, settings: impl Into<ParseSettings>) -> JsonLines<'_> {
+    JsonLines {
+        src: in_str,
+        pos: 0,
+        line: 1,
+        settings: settings.into(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct JsonLines<'a> {
+    src: &'a str,
+    pos: usize,
+    line: usize,
+    settings: ParseSettings,
+}
+
+impl JsonLines<'_> {
+    /// 从`self.pos`开始的这条记录之后的换行符。json的字符串中不能有换行，直接按行切分；
+    /// 方言的字符串和注释可以跨行，换行从值结束的位置开始找，值没有结束时同样按行切分
+    fn find_newline(&self) -> Option<usize> {
+        let dialect = self.settings.dialect;
+        let mut from = self.pos;
+        if dialect != Dialect::Json {
+            let start = skip_blank(self.src, self.pos, dialect);
+            if !self.src[self.pos..start].contains('\n') {
+                if let Some(end) = find_value_end(self.src, start, dialect) {
+                    from = end;
+                }
+            }
+        }
+        self.src[from..].find('\n').map(|n| from + n)
+    }
+}
+
+/// 完整的记录原样返回，方言输入时与补全的结果一样按照`keep_dialect`输出，残缺的记录视为错误。
+/// 是否完整由完整的扫描决定，与`allow`等补全设置无关；`non_finite`同样作用于完整的记录
+pub(crate) fn check_complete(record: &str, settings: &ParseSettings) -> Result<String, Error> {
+    // 在值结束之前就断开了
+    complete_record(record, settings)?.ok_or(Error::Uncompletable {
+        offset: record.len(),
+    })
+}
+
+/// 记录是完整的单个值时返回`Some`，值还没有结束时返回`None`，
+/// 值之后还有其它内容时返回错误，例如`[1] [2]`
+fn complete_record(record: &str, settings: &ParseSettings) -> Result<Option<String>, Error> {
+    let settings = settings.for_complete();
+    let dialect = settings.dialect;
+    let start = skip_blank(record, 0, dialect);
+    if let Some(end) = find_value_end(record, start, dialect) {
+        let rest = skip_blank(record, end, dialect);
+        if rest < record.len() {
+            return Err(Error::Uncompletable { offset: rest });
+        }
+    }
+    if !is_complete(record, &settings)? {
+        return Ok(None);
+    }
+    // 方言输入与补全的结果一样翻译为严格的json，除非设置了`keep_dialect`；
+    // 宽松模式下非法的转义同样需要与补全的结果一样修复
+    let keeps_source =
+        dialect == Dialect::Json || (settings.keep_dialect && dialect == Dialect::Json5);
+    if keeps_source
+        && settings.non_finite == NonFinitePolicy::Preserve
+        && settings.escape_mode == EscapeMode::Strict
+    {
+        Ok(Some(record.to_string()))
+    } else {
+        Parser::parser_with(record, settings).map(Some)
+    }
+}
+
+/// 扫描到末尾时所有的括号和字符串都已经闭合，并且最后一个右括号之后没有其它内容，
+/// 根节点是原子值时则必须是一个合法的原子值
+fn is_complete(src: &str, settings: &ParseSettings) -> Result<bool, Error> {
+    let mut parser = Parser::with_settings(src, settings.clone());
+    parser.parse();
+    let trace = parser.parse_tracer();
+    if let Some(err) = trace.error {
+        return Err(parser.source_error(err));
+    }
+    // 方言输入末尾尚未确定的部分，以及宽松模式下修复过的转义，需要对完整的json重新判断
+    if let Some((src, settings)) = parser.reparse_src() {
+        return is_complete(&src, &settings).map_err(|err| parser.reparsed_error(err));
+    }
+    if !trace.stack.is_empty() || trace.in_string {
+        return Ok(false);
+    }
+    let src = parser.src_str();
+    Ok(match trace.last_rbracket {
+        Some(idx) => src[idx + 1..].trim_matches(is_space).is_empty(),
+        None => Value::atom(src.trim_matches(is_space)).is_some(),
+    })
+}
+
+impl Iterator for JsonLines<'_> {
+    type Item = JsonLine;
+
+    fn next(&mut self) -> Option<JsonLine> {
+        while self.pos < self.src.len() {
+            let line = self.line;
+            let (end, is_last) = match self.find_newline() {
+                Some(end) => (end, false),
+                None => (self.src.len(), true),
+            };
+            let raw = &self.src[self.pos..end];
+            // 字符串中的换行也计入行号
+            self.line += raw.matches('\n').count() + 1;
+            self.pos = if is_last { end } else { end + 1 };
+
+            let record = raw.trim_end_matches('\r');
+            // 空行以及只有注释的行
+            if skip_blank(record, 0, self.settings.dialect) == record.len() {
+                continue;
+            }
+            let (result, truncated) = if is_last {
+                match complete_record(record, &self.settings) {
+                    Ok(Some(record)) => (Ok(record), false),
+                    Ok(None) => {
+                        let res = Parser::parser_with(record, self.settings.clone());
+                        (res, true)
+                    }
+                    Err(err) => (Err(err), false),
+                }
+            } else {
+                (check_complete(record, &self.settings), false)
+            };
+            return Some(JsonLine {
+                line,
+                result,
+                truncated,
+            });
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::json_lines;
+    use crate::{Allow, Dialect, Error, EscapeMode, NonFinitePolicy, ParseSettings};
+
+    #[test]
+    fn test_json_lines() {
+        let src = "{\"a\": 1}\r\n\n[1, 2\n\"x\\ny\"\n42\n{\"b\": [1, tr";
+        let lines: Vec<_> = json_lines(src, Allow::ALL).collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0].result.as_deref(), Ok("{\"a\": 1}"));
+        assert_eq!(lines[1].line, 3);
+        assert_eq!(lines[1].result, Err(Error::Uncompletable { offset: 5 }));
+        assert_eq!(lines[2].result.as_deref(), Ok("\"x\\ny\""));
+        assert_eq!((lines[3].line, lines[3].result.as_deref()), (5, Ok("42")));
+        assert_eq!(lines[4].line, 6);
+        assert_eq!(lines[4].result.as_deref(), Ok("{\"b\": [1,true]}"));
+        assert!(lines[4].truncated);
+
+        // 最后一条记录恰好是完整的
+        let lines: Vec<_> = json_lines("1\n[2]", Allow::ALL).collect();
+        assert_eq!(lines[1].result.as_deref(), Ok("[2]"));
+        assert!(!lines[1].truncated);
+        assert_eq!(json_lines("\n \n", Allow::ALL).count(), 0);
+
+        // 完整的记录与补全的设置无关
+        let results: Vec<_> = json_lines("1\n\"x\"\n[2]", ParseSettings::default())
+            .map(|line| line.result)
+            .collect();
+        assert_eq!(
+            results,
+            [Ok("1".into()), Ok("\"x\"".into()), Ok("[2]".into())]
+        );
+        let monotonic = ParseSettings::new().allow(Allow::ALL).monotonic(true);
+        let lines: Vec<_> = json_lines("1\n2", monotonic).collect();
+        assert_eq!(lines[0].result.as_deref(), Ok("1"));
+        assert!(!lines[1].truncated);
+        let json5 = ParseSettings::new().dialect(Dialect::Json5);
+        let lines: Vec<_> = json_lines("{a: 1}\n0x10\n[1, ", json5.clone()).collect();
+        assert_eq!(lines[1].result.as_deref(), Ok("16"));
+        assert!(lines[2].truncated);
+        // 完整的记录与补全的结果格式相同，与non_finite无关
+        for policy in [NonFinitePolicy::Preserve, NonFinitePolicy::Null] {
+            let results: Vec<_> = json_lines("{a: 1}\n[2, ", json5.clone().non_finite(policy))
+                .map(|line| line.result)
+                .collect();
+            assert_eq!(results, [Ok("{\"a\": 1}".into()), Ok("[2]".into())]);
+            let keep = json5.clone().non_finite(policy).keep_dialect(true);
+            let results: Vec<_> = json_lines("{a: 1}\n[2, ", keep)
+                .map(|line| line.result)
+                .collect();
+            assert_eq!(results, [Ok("{a: 1}".into()), Ok("[2]".into())]);
+        }
+
+        // 完整的记录同样受non_finite的约束
+        let settings = ParseSettings::new().allow(Allow::ALL);
+        let results: Vec<_> = json_lines(
+            "[NaN]\n1\n[-Inf",
+            settings.clone().non_finite(NonFinitePolicy::Error),
+        )
+        .map(|line| line.result)
+        .collect();
+        assert_eq!(
+            results,
+            [
+                Err(Error::NonFinite { offset: 1 }),
+                Ok("1".into()),
+                Err(Error::NonFinite { offset: 1 })
+            ]
+        );
+        let lines: Vec<_> =
+            json_lines("[NaN]\n[-Inf", settings.non_finite(NonFinitePolicy::Null)).collect();
+        assert_eq!(lines[0].result.as_deref(), Ok("[null]"));
+        assert_eq!(lines[1].result.as_deref(), Ok("[null]"));
+
+        // 宽松模式下完整的记录与残缺的记录一样修复非法的转义
+        let lenient = ParseSettings::new()
+            .allow(Allow::ALL)
+            .escape_mode(EscapeMode::Lenient);
+        let results: Vec<_> = json_lines("[\"a\\x\"]\n[\"b\\x", lenient)
+            .map(|line| line.result)
+            .collect();
+        assert_eq!(
+            results,
+            [Ok(r#"["a\\x"]"#.into()), Ok(r#"["b\\x"]"#.into())]
+        );
+    }
+
+    #[test]
+    fn test_json_lines_split() {
+        // 字符串中没有结束的引号只影响这一行
+        let results: Vec<_> = json_lines("{\"a\": \"x}\n{\"b\": 1}\n[3]\n", Allow::ALL)
+            .map(|line| (line.line, line.result.is_ok(), line.truncated))
+            .collect();
+        assert_eq!(
+            results,
+            [(1, false, false), (2, true, false), (3, true, false)]
+        );
+        // 一行只能有一个值
+        let lines: Vec<_> = json_lines("[1] [2]\n[1] [2]", Allow::ALL).collect();
+        assert_eq!(lines[0].result, Err(Error::Uncompletable { offset: 4 }));
+        assert_eq!(lines[1].result, Err(Error::Uncompletable { offset: 4 }));
+        assert!(!lines[1].truncated);
+
+        // 方言按照各自的字符串切分，字符串和注释中的换行不会切分记录
+        let json5 = ParseSettings::new().dialect(Dialect::Json5);
+        let src = "{a: 'it\"s'}\n{b: 'x\\\ny'} // c\n/* d */\n[2, ";
+        let lines: Vec<_> = json_lines(src, json5.keep_dialect(true)).collect();
+        let results: Vec<_> = lines.iter().map(|line| line.result.as_deref()).collect();
+        assert_eq!(
+            results,
+            [Ok("{a: 'it\"s'}"), Ok("{b: 'x\\\ny'} // c"), Ok("[2]")]
+        );
+        assert_eq!((lines[2].line, lines[2].truncated), (5, true));
+        let python = ParseSettings::new().dialect(Dialect::PythonLiteral);
+        let results: Vec<_> = json_lines("['a\"']\n'''x\ny'''\n[1]", python)
+            .map(|line| line.result)
+            .collect();
+        assert_eq!(
+            results,
+            [
+                Ok("[\"a\\\"\"]".into()),
+                Ok("\"x\\ny\"".into()),
+                Ok("[1]".into())
+            ]
+        );
+    }
+}
@@ -1,7 +1,40 @@
+mod allow;
+mod array_items;
+mod completion;
+mod de;
+mod diagnostic;
+mod dialect;
+mod documents;
+mod error;
+mod events;
+mod extract;
+mod json_lines;
 pub mod parser;
+mod pointer;
+#[cfg(feature = "python")]
+mod python;
+mod repair;
+mod streaming;
 #[cfg(test)]
 mod test_utils;
 mod utils;
+mod value;
 mod value_parser;
 
-pub use parser::Parser;
+pub use allow::Allow;
+pub use array_items::{ArrayItem, ArrayItems};
+pub use completion::Completion;
+pub use de::{from_partial_str, from_partial_str_with, FromPartialError};
+pub use diagnostic::{ParseTrace, Warning};
+pub use dialect::Dialect;
+pub use documents::{documents, parse_documents, Document, DocumentPolicy, Documents};
+pub use error::Error;
+pub use events::{Event, EventReader};
+pub use extract::{extract_json, find_json, find_json_with, Extraction};
+pub use json_lines::{json_lines, JsonLine, JsonLines};
+pub use parser::{
+    EscapeMode, NonFinitePolicy, NumberPolicy, ParseSettings, Parser, PartialKeyPolicy,
+};
+pub use repair::{repair, Fix, Repaired};
+pub use streaming::StreamingParser;
+pub use value::loads;
@@ -1,16 +1,22 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
 use crate::{
-    utils::{add_title, RunState},
-    value_parser,
+    completion::Completion,
+    diagnostic::{ParseTrace, Warning},
+    dialect::{Dialect, DialectInput},
+    utils::{log_warn, RunState},
+    value_parser, Allow, Error,
 };
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub enum State {
     InStr(EscapeCnt),
     #[default]
     NotInStr,
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub struct EscapeCnt {
     // 这是一个取值范围为[0, 2)的计数器
     cnt: usize,
@@ -56,38 +62,58 @@ impl EscapeCnt {
         )
     }
 
+    /// Ok(true)表示转义序列已经结束，Err表示转义序列不合法
     #[inline]
-    fn valid_esc_char(&mut self, c: &char) -> bool {
+    fn valid_esc_char(&mut self, c: &char) -> Result<bool, ()> {
         if self.cnt == 0 {
-            return false;
+            return Ok(false);
         }
         // 实际上，在本文件中的proptest无法覆盖\u的情况
         if !self.u_mode && *c != 'u' {
-            matches!(c, '"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't')
+            if matches!(c, '"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't') {
+                Ok(true)
+            } else {
+                Err(())
+            }
         } else if !self.u_mode && *c == 'u' {
             self.u_mode = true;
-            false
+            Ok(false)
         } else if self.u_mode && self.u_cnt < 3 && Self::valid_hex_char(c) {
             self.u_cnt += 1;
-            false
+            Ok(false)
         } else if self.u_mode && self.u_cnt == 3 && Self::valid_hex_char(c) {
-            true
+            Ok(true)
         } else {
-            panic!("Should not reach here")
+            Err(())
         }
     }
 
+    fn reset(&mut self) {
+        self.cnt = 0;
+        self.u_cnt = 0;
+        self.u_mode = false;
+    }
+
+    /// 遇到非法的转义时返回`CharType::InvalidEscape`，此时状态已经重置，
+    /// 当前字符并没有被消耗，由调用者决定是否把它当作普通字符重新输入
     pub fn input(&mut self, c: char) -> CharType {
         if self.cnt == 0 && c == '\\' {
             self.cnt = 1;
             CharType::Escape
         } else if self.cnt == 0 && c == '"' {
             CharType::Quotation
-        } else if self.cnt == 1 && self.valid_esc_char(&c) {
-            self.cnt = 0;
-            self.u_cnt = 0;
-            self.u_mode = false;
-            CharType::Normal
+        } else if self.cnt == 1 {
+            match self.valid_esc_char(&c) {
+                Ok(true) => {
+                    self.reset();
+                    CharType::Normal
+                }
+                Ok(false) => CharType::Special,
+                Err(()) => {
+                    self.reset();
+                    CharType::InvalidEscape
+                }
+            }
         } else {
             CharType::Special
         }
@@ -111,6 +137,7 @@ pub enum CharType {
     #[default]
     Normal,
     Special,
+    InvalidEscape, // 字符串中不合法的转义
 }
 
 impl CharType {
@@ -150,7 +177,7 @@ impl CharType {
             Self::RFB => "]",
             Self::LCB => "{",
             Self::RCB => "}",
-            Self::Normal | Self::Special => "",
+            Self::Normal | Self::Special | Self::InvalidEscape => "",
         };
         res.to_string()
     }
@@ -178,89 +205,696 @@ impl CharType {
     }
 }
 
-#[derive(Default, Debug)]
-struct ParseSettings {
-    // array and obj will always be cut
-    allow_null: bool,
-    allow_bool: bool,
-    allow_number: bool,
-    allow_string: bool,
-    allow_infinity: bool,
-    allow_ninfinity: bool,
-    allow_nan: bool,
+/// 扫描时的回调，每遇到一个字符串之外的括号、逗号或冒号时调用一次，
+/// 此时栈已经更新完毕
+pub(crate) trait ScanVisitor {
+    fn visit(&mut self, src: &str, idx: usize, char_type: CharType);
+
+    /// 字符串开始或结束的引号
+    fn visit_quote(&mut self, _src: &str, _idx: usize) {}
+}
+
+impl ScanVisitor for () {
+    #[inline]
+    fn visit(&mut self, _src: &str, _idx: usize, _char_type: CharType) {}
+}
+
+/// 遇到非法的转义序列（如`\x41`、`\u12G4`）时的处理方式
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EscapeMode {
+    /// 返回[`Error::InvalidEscape`]
+    #[default]
+    Strict,
+    /// 把反斜杠本身转义，即`"\x41"`补全为`"\\x41"`
+    Lenient,
+}
+
+/// 输出中`NaN`、`Infinity`以及`-Infinity`的处理方式，它们不是RFC 8259中合法的json
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NonFinitePolicy {
+    /// 原样保留
+    #[default]
+    Preserve,
+    /// 替换为`null`
+    Null,
+    /// 替换为字符串，例如`"NaN"`
+    String,
+    /// 返回[`Error::NonFinite`]
+    Error,
+}
+
+impl NonFinitePolicy {
+    /// 按照策略替换一个原子值，`offset`是它在输出中的位置
+    pub(crate) fn apply<'s>(&self, atom: &'s str, offset: usize) -> Result<Cow<'s, str>, Error> {
+        if !matches!(atom, "NaN" | "Infinity" | "-Infinity") {
+            return Ok(Cow::Borrowed(atom));
+        }
+        match self {
+            Self::Preserve => Ok(Cow::Borrowed(atom)),
+            Self::Null => Ok(Cow::Borrowed("null")),
+            Self::String => Ok(Cow::Owned(format!("\"{}\"", atom))),
+            Self::Error => Err(Error::NonFinite { offset }),
+        }
+    }
+
+    /// 替换补全结果中所有字符串之外的`NaN`、`Infinity`以及`-Infinity`，`text`必须是严格的json
+    pub(crate) fn apply_text(&self, text: &str) -> Result<String, Error> {
+        self.apply_text_at(text, &mut 0)
+    }
+
+    /// 与`apply_text`相同，同时把`text`中的位置`pos`调整为替换之后的位置，
+    /// 位于被替换的值中间时移动到它的开头
+    pub(crate) fn apply_text_at(&self, text: &str, pos: &mut usize) -> Result<String, Error> {
+        if *self == Self::Preserve {
+            return Ok(text.to_string());
+        }
+        let old_pos = *pos;
+        let mut moved = None;
+        let mut res = String::with_capacity(text.len());
+        let mut in_str = false;
+        let mut escaped = false;
+        let mut last = 0;
+        for (idx, c) in text.char_indices() {
+            if idx < last {
+                continue;
+            }
+            match c {
+                _ if escaped => escaped = false,
+                '\\' if in_str => escaped = true,
+                '"' => in_str = !in_str,
+                'N' | 'I' | '-' if !in_str => {
+                    let end = text[idx..]
+                        .find(|c: char| !c.is_ascii_alphanumeric() && c != '-')
+                        .map_or(text.len(), |len| idx + len);
+                    let atom = &text[idx..end];
+                    if matches!(atom, "NaN" | "Infinity" | "-Infinity") {
+                        res.push_str(&text[last..idx]);
+                        if moved.is_none() && old_pos < end {
+                            moved = Some(res.len() - (idx - old_pos.min(idx)));
+                        }
+                        res.push_str(&self.apply(atom, idx)?);
+                        last = end;
+                    }
+                }
+                _ => {}
+            }
+        }
+        *pos = moved.unwrap_or(res.len() + old_pos.saturating_sub(last));
+        res.push_str(&text[last..]);
+        Ok(res)
+    }
+}
+
+/// 输入末尾残缺的数字的处理方式，对数组元素、对象的值以及根节点都有效，
+/// 只在[`Allow::NUM`]允许残缺的数字时起作用
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NumberPolicy {
+    /// 丢弃不完整的数字，例如`12.`、`1e-`以及`-`；`12`这样本身合法的数字仍然保留
+    Drop,
+    /// 保留最长的合法前缀，例如`12.`补全为`12`，`1e-`补全为`1`，只有`-`时丢弃
+    #[default]
+    LongestPrefix,
+    /// 只保留后面已经跟着空白的完整数字，`12`之后可能变成`1234`，因此不会出现
+    Delimited,
+}
+
+impl NumberPolicy {
+    /// 按照策略解析末尾的数字，`s`是最后一个结构字符之后的全部内容
+    fn parse_num(&self, s: &str) -> Result<value_parser::VParserRes, Error> {
+        let atom = s.trim_end_matches(value_parser::is_space);
+        let res = match self {
+            // 指数部分残缺时退回到指数之前，后面已经有空白时说明数字本身不合法
+            Self::LongestPrefix if atom.len() == s.len() => {
+                value_parser::parse_num(s).or_else(|err| match s.find(['e', 'E']) {
+                    Some(idx) => value_parser::parse_num(&s[..idx]),
+                    None => Err(err),
+                })
+            }
+            _ => value_parser::parse_num(s),
+        }?;
+        let is_whole = res.amend_value() == atom;
+        match self {
+            Self::Drop if !is_whole => Err(Error::Uncompletable { offset: 0 }),
+            Self::Delimited if !is_whole || atom.len() == s.len() => {
+                Err(Error::Uncompletable { offset: 0 })
+            }
+            _ => Ok(res),
+        }
+    }
+}
+
+/// 对象中已经出现了key、但是还没有值时的处理方式，
+/// 对完整的key以及尚未输入完的key都有效
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum PartialKeyPolicy {
+    /// 连同key一起丢弃
+    #[default]
+    Drop,
+    /// 保留key，值为`null`
+    KeepWithNull,
+    /// 保留key，值为给定的占位值
+    KeepWithPlaceholder(serde_json::Value),
+}
+
+impl PartialKeyPolicy {
+    /// 占位值的json文本，丢弃key时返回`None`
+    fn placeholder(&self) -> Option<String> {
+        match self {
+            Self::Drop => None,
+            Self::KeepWithNull => Some("null".to_string()),
+            Self::KeepWithPlaceholder(value) => Some(value.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSettings {
+    // 完整的值总是会被保留，allow只决定残缺的值能否出现在结果中
+    allow: Allow,
+    pub(crate) escape_mode: EscapeMode,
+    pub(crate) dialect: Dialect,
+    pub(crate) keep_dialect: bool,
+    pub(crate) non_finite: NonFinitePolicy,
+    pub(crate) empty_value: Option<String>,
+    monotonic: bool,
+    partial_key: PartialKeyPolicy,
+    number: NumberPolicy,
+}
+
+impl Default for ParseSettings {
+    fn default() -> Self {
+        // 默认只补全数组和对象，残缺的原子值都会被截掉
+        ParseSettings {
+            allow: Allow::COLLECTION,
+            escape_mode: EscapeMode::default(),
+            dialect: Dialect::default(),
+            keep_dialect: false,
+            non_finite: NonFinitePolicy::default(),
+            empty_value: None,
+            monotonic: false,
+            partial_key: PartialKeyPolicy::default(),
+            number: NumberPolicy::default(),
+        }
+    }
+}
+
+impl ParseSettings {
+    pub fn new() -> ParseSettings {
+        Self::default()
+    }
+
+    pub fn allow(mut self, allow: Allow) -> ParseSettings {
+        self.allow = allow;
+        self
+    }
+
+    pub fn escape_mode(mut self, escape_mode: EscapeMode) -> ParseSettings {
+        self.escape_mode = escape_mode;
+        self
+    }
+
+    /// 输入的方言，默认为json
+    pub fn dialect(mut self, dialect: Dialect) -> ParseSettings {
+        self.dialect = dialect;
+        self
+    }
+
+    /// 补全方言输入时保留原本的写法，只在末尾补上缺失的部分；默认输出严格的json。
+    /// 补上的部分总是合法的json，因此对于JSON5来说结果仍然是合法的JSON5；
+    /// Python字面量总是输出严格的json
+    pub fn keep_dialect(mut self, keep_dialect: bool) -> ParseSettings {
+        self.keep_dialect = keep_dialect;
+        self
+    }
+
+    /// 输出中`NaN`、`Infinity`以及`-Infinity`的处理方式，
+    /// 对[`Parser::complete`]、[`Parser::completion`]、[`crate::loads`]以及JSON Lines和多文档中完整的值都有效，
+    /// 残缺的`-Inf`会先补全再处理
+    pub fn non_finite(mut self, non_finite: NonFinitePolicy) -> ParseSettings {
+        self.non_finite = non_finite;
+        self
+    }
+
+    /// 补全的结果中没有任何值时返回的值，默认返回错误。包括输入为空或者只有空白，
+    /// 以及根节点的原子值被截掉：例如`-`，或者默认设置下不允许残缺数字时的`123`
+    pub fn empty_value(mut self, value: serde_json::Value) -> ParseSettings {
+        self.empty_value = Some(value.to_string());
+        self
+    }
+
+    /// 单调补全：输入变长时，每次的结果都是上一次结果在结构上的延伸，适合流式渲染。
+    /// 残缺的字符串只会变长，数字和关键字等原子值要等到后面出现空白或分隔符、
+    /// 类型和取值都不会再变时才出现在结果中
+    pub fn monotonic(mut self, monotonic: bool) -> ParseSettings {
+        self.monotonic = monotonic;
+        self
+    }
+
+    /// 对象中只有key、没有值时的处理方式，默认丢弃key。
+    /// 占位值在之后可能被真正的值替换，因此单调模式下总是丢弃
+    pub fn partial_key(mut self, policy: PartialKeyPolicy) -> ParseSettings {
+        self.partial_key = policy;
+        self
+    }
+
+    /// 输入末尾残缺的数字的处理方式，默认保留最长的合法前缀
+    pub fn number(mut self, policy: NumberPolicy) -> ParseSettings {
+        self.number = policy;
+        self
+    }
+
+    /// 解析已知完整的值时使用的设置：只保留转义、方言以及特殊浮点数的处理方式，
+    /// 与补全有关的设置都恢复为默认值，例如根节点的数字不会因为可能尚未结束而被丢弃
+    pub(crate) fn for_complete(&self) -> ParseSettings {
+        ParseSettings {
+            allow: Allow::ALL,
+            escape_mode: self.escape_mode,
+            dialect: self.dialect,
+            keep_dialect: self.keep_dialect,
+            non_finite: self.non_finite,
+            ..Default::default()
+        }
+    }
+
+    /// 缺少值的key需要保留时，补在key之后的占位值
+    pub(crate) fn key_placeholder(&self) -> Option<String> {
+        if self.monotonic {
+            None
+        } else {
+            self.partial_key.placeholder()
+        }
+    }
+
+    #[inline]
+    fn allows(&self, flag: Allow) -> bool {
+        self.allow.contains(flag)
+    }
+
+    #[inline]
+    pub(crate) fn allows_collection(&self, char_type: CharType) -> bool {
+        match char_type {
+            CharType::LFB => self.allows(Allow::ARR),
+            CharType::LCB => self.allows(Allow::OBJ),
+            _ => true,
+        }
+    }
+}
+
+impl From<Allow> for ParseSettings {
+    fn from(allow: Allow) -> Self {
+        ParseSettings::new().allow(allow)
+    }
 }
 
 #[derive(Default, Debug)]
 pub struct Parser<'a> {
     stack: Vec<(usize, CharType)>,
     state: State,
-    src_str: &'a str,
+    src_str: Cow<'a, str>,
     last_sep: Option<usize>,
     last_colon: Option<usize>,
     last_rbracket: Option<usize>,
-    is_parsed: RunState<String>,
+    is_parsed: RunState<Error>,
     settings: ParseSettings,
+    // 最近一个转义符号的位置，以及宽松模式下所有非法转义的反斜杠的位置
+    last_escape: usize,
+    invalid_escapes: Vec<usize>,
+    warnings: Vec<Warning>,
+    // 方言输入的原文，src_str中只有已经确定的翻译结果
+    dialect: Option<Box<DialectInput>>,
+    // 宽松模式下修复过非法转义之后，对修复后的输入增量扫描的parser，以及已经交给它的输入的长度
+    repaired: Option<Box<Parser<'static>>>,
+    synced: usize,
 }
 
 impl<'a> Parser<'a> {
+    /// 使用默认设置补全，只保留残缺的数组和对象。根节点的原子值无法确定是否已经结束，
+    /// 因此`123`和`"ab`都会返回[`Error::Uncompletable`]，需要时参见[`ParseSettings::empty_value`]
     #[allow(unused)]
-    pub fn parser(in_str: &'a str) -> Result<String, String> {
+    pub fn parser(in_str: &'a str) -> Result<String, Error> {
         // 接收需要补全的字符串，返回补全后的字符串
         // 内部需要构造parser
-        if in_str.is_empty() {
-            return Err("Input str is Empty".to_string());
+        Self::parser_with(in_str, ParseSettings::default())
+    }
+
+    /// 与[`Parser::parser`]相同，但是由`settings`决定哪些残缺的值可以保留，
+    /// 例如`Parser::parser_with(s, Allow::ALL)`。
+    pub fn parser_with(
+        in_str: &'a str,
+        settings: impl Into<ParseSettings>,
+    ) -> Result<String, Error> {
+        let mut parser = Self::with_settings(in_str, settings.into());
+        parser.parse();
+        parser.complete()
+    }
+
+    /// 与[`Parser::parser_with`]相同，但是返回[`Completion`]，其中包含保留与丢弃的范围等信息
+    pub fn completion_with(
+        in_str: &'a str,
+        settings: impl Into<ParseSettings>,
+    ) -> Result<Completion, Error> {
+        let mut parser = Self::with_settings(in_str, settings);
+        parser.parse();
+        parser.completion()
+    }
+
+    /// 需要拿到[`Parser::warnings`]等信息时，依次调用`parse`和`complete`；
+    /// 没有调用`parse`时`complete`也可以使用，只是不会保留扫描的结果
+    pub fn with_settings(in_str: &'a str, settings: impl Into<ParseSettings>) -> Parser<'a> {
+        let settings = settings.into();
+        if settings.dialect == Dialect::Json {
+            return Parser {
+                src_str: in_str.into(),
+                settings,
+                ..Default::default()
+            };
         }
-        let mut parser = Parser {
-            src_str: in_str,
+        let mut dialect = DialectInput::new(settings.dialect);
+        Parser {
+            src_str: dialect.push(in_str).into(),
+            settings,
+            dialect: Some(Box::new(dialect)),
             ..Default::default()
-        };
-        parser.parse();
-        parser.amend().or(Err("Amend Error in parser".to_string()))
+        }
     }
 
-    pub fn stack_tracer(&self) -> String {
-        let mut s = String::new();
-        s.push_str(&add_title("Stack Tracer"));
-        for (idx, (c_idx, item)) in self.stack.iter().enumerate() {
-            let info = format!(
-                "idx: {}, item {} at {} of str\n",
-                idx,
-                item.type_string(),
-                c_idx
-            );
-            s.push_str(&info)
+    pub(crate) fn parser_owned(settings: ParseSettings) -> Parser<'static> {
+        let dialect = (settings.dialect != Dialect::Json)
+            .then(|| Box::new(DialectInput::new(settings.dialect)));
+        Parser {
+            src_str: Cow::Owned(String::new()),
+            settings,
+            dialect,
+            ..Default::default()
         }
-        s
     }
 
-    pub fn parse_tracer(&self) -> String {
-        let mut s = String::new();
-        s.push_str(&add_title("Parse Tracer"));
-        s.push_str(&self.stack_tracer());
-        s.push_str(&add_title("State"));
-        s.push_str(&format!("{:?}\n", self.state));
-        s.push_str(&add_title("Last Sep"));
-        s.push_str(&format!(
-            "{:?}, {}\n",
-            self.last_sep,
-            self.last_sep
-                .map(|i| self.src_str[i..].to_string())
-                .unwrap_or("None".to_string())
-        ));
-        if let RunState::Error(s1) = &self.is_parsed {
-            s.push_str(&add_title("Parse State"));
-            s.push_str(&format!("{}\n", s1));
+    pub fn complete(&self) -> Result<String, Error> {
+        let res = self.amend().map_err(|err| self.source_error(err))?;
+        // 在还原为方言之前处理，这时的结果总是严格的json
+        let res = self
+            .settings
+            .non_finite
+            .apply_text(&res)
+            .map_err(|err| self.reparsed_error(err))?;
+        Ok(self.kept_dialect(&res).unwrap_or(res))
+    }
+
+    /// 设置了`keep_dialect`的JSON5输入，把补全得到的json还原为原本的写法
+    fn kept_dialect(&self, completed: &str) -> Option<String> {
+        match &self.dialect {
+            Some(dialect)
+                if self.settings.keep_dialect && self.settings.dialect == Dialect::Json5 =>
+            {
+                let text = self.src_str.to_string() + &dialect.tail();
+                Some(dialect.to_source(&text, completed))
+            }
+            _ => None,
         }
-        s
+    }
+
+    /// 方言输入时，保留的前缀和丢弃的范围同样相对于原始的输入，补全的结果是严格的json；
+    /// 设置了`keep_dialect`的JSON5输入与[`Parser::complete`]相同，保留原本的写法
+    pub fn completion(&self) -> Result<Completion, Error> {
+        let (text, mut pending) = self
+            .amend_with_pending()
+            .map_err(|err| self.source_error(err))?;
+        let text = self
+            .settings
+            .non_finite
+            .apply_text_at(&text, &mut pending)
+            .map_err(|err| self.reparsed_error(err))?;
+        match &self.dialect {
+            Some(dialect) => {
+                let kept = self.kept_dialect(&text);
+                let res = Completion::new(dialect.source(), text, pending);
+                Ok(match kept {
+                    Some(text) => res.with_text(dialect.source(), text),
+                    None => res,
+                })
+            }
+            None => Ok(Completion::new(&self.src_str, text, pending)),
+        }
+    }
+
+    /// 尚未闭合的左括号及其位置，由外向内
+    pub fn stack_tracer(&self) -> Vec<(usize, char)> {
+        self.stack
+            .iter()
+            .filter_map(|(idx, item)| item.type_string().chars().next().map(|c| (*idx, c)))
+            .collect()
+    }
+
+    pub fn parse_tracer(&self) -> ParseTrace {
+        ParseTrace {
+            stack: self.stack_tracer(),
+            in_string: matches!(self.state, State::InStr(_)),
+            last_sep: self.last_sep,
+            last_colon: self.last_colon,
+            last_rbracket: self.last_rbracket,
+            error: match &self.is_parsed {
+                RunState::Error(err) => Some(err.clone()),
+                _ => None,
+            },
+            warnings: self.warnings.clone(),
+        }
+    }
+
+    /// 扫描过程中收集到的警告
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
     }
 
     pub fn parse(&mut self) {
         // 内部需要对附加的字符串src_str进行解析，并且返回修改后的结构体
         assert!(self.is_parsed.is_none());
         self.is_parsed = RunState::Success;
+        self.parse_from(0, &mut ());
+        self.check_dialect_tail();
+    }
+
+    /// 与`parse`相同，但是会把扫描到的结构字符交给`visitor`，用于一遍扫描直接构造值
+    pub(crate) fn parse_with<V: ScanVisitor>(&mut self, visitor: &mut V) {
+        assert!(self.is_parsed.is_none());
+        self.is_parsed = RunState::Success;
+        self.parse_from(0, visitor);
+        self.check_dialect_tail();
+    }
+
+    /// 一次性扫描完整的输入时，末尾尚未确定的部分中无法翻译的字面量同样视为扫描的错误；
+    /// 增量扫描时只在补全时检查，见[`Parser::dialect_error`]
+    fn check_dialect_tail(&mut self) {
+        if !self.is_parsed.is_error() {
+            if let Some(err) = self.dialect_error() {
+                self.is_parsed = RunState::Error(err);
+            }
+        }
+    }
+
+    /// 方言输入中无法翻译的字面量，包括末尾尚未确定的部分
+    pub(crate) fn dialect_error(&self) -> Option<Error> {
+        let offset = self.dialect.as_ref()?.error()?;
+        Some(Error::Uncompletable { offset })
+    }
+
+    /// 追加输入并且只扫描新增的部分，栈和各个分隔符的位置在多次调用之间保留
+    pub(crate) fn push_str(&mut self, chunk: &str) {
+        self.push_str_with(chunk, &mut ());
+    }
+
+    pub(crate) fn push_str_with<V: ScanVisitor>(&mut self, chunk: &str, visitor: &mut V) {
+        let chunk = match &mut self.dialect {
+            Some(dialect) => Cow::Owned(dialect.push(chunk)),
+            None => Cow::Borrowed(chunk),
+        };
+        let start = self.src_str.len();
+        self.src_str.to_mut().push_str(&chunk);
+        if !self.is_parsed.is_error() {
+            self.parse_from(start, visitor);
+        }
+    }
+
+    pub(crate) fn src_str(&self) -> &str {
+        &self.src_str
+    }
+
+    /// 方言输入的末尾尚未确定的部分按照输入已经结束翻译得到的json
+    pub(crate) fn dialect_tail(&self) -> String {
+        self.dialect
+            .as_ref()
+            .map(|dialect| dialect.tail())
+            .unwrap_or_default()
+    }
+
+    /// 原始的输入，方言输入时与`src_str`不同
+    pub(crate) fn input_str(&self) -> &str {
+        match &self.dialect {
+            Some(dialect) => dialect.source(),
+            None => &self.src_str,
+        }
+    }
+
+    /// 需要对完整的json重新扫描时，返回这个json以及扫描使用的设置：
+    /// 宽松模式下修复过非法转义，或者方言输入的末尾还有尚未确定的部分
+    pub(crate) fn reparse_src(&self) -> Option<(String, ParseSettings)> {
+        let tail = self
+            .dialect
+            .as_ref()
+            .map(|dialect| dialect.tail())
+            .filter(|tail| !tail.is_empty());
+        if tail.is_none() && self.invalid_escapes.is_empty() {
+            return None;
+        }
+        let mut src = self
+            .repaired_src()
+            .unwrap_or_else(|| self.src_str.to_string());
+        src.push_str(tail.as_deref().unwrap_or_default());
+        Some((src, self.settings.clone().dialect(Dialect::Json)))
+    }
 
-        for (idx, c) in self.src_str.char_indices() {
-            let char_type = self.state_machine_input(c);
+    /// 方言输入时把错误中的offset从翻译得到的json还原到原始的输入中
+    pub(crate) fn source_error(&self, err: Error) -> Error {
+        match &self.dialect {
+            Some(dialect) => err.map_offset(|offset| dialect.source_offset(offset)),
+            None => err,
+        }
+    }
+
+    /// 翻译得到的json中的值`range`在原始输入中的范围，`end`是值之后的逗号或者括号的位置
+    pub(crate) fn source_range(&self, range: Range<usize>, end: usize) -> Range<usize> {
+        match &self.dialect {
+            Some(dialect) => dialect.source_range(range.start, end),
+            None => range,
+        }
+    }
+
+    /// 对`reparse_src`扫描得到的错误，去掉修复转义时插入的反斜杠之后再还原到原始的输入中
+    pub(crate) fn reparsed_error(&self, err: Error) -> Error {
+        self.source_error(err.map_offset(|offset| self.unrepaired_offset(offset)))
+    }
+
+    fn unrepaired_offset(&self, offset: usize) -> usize {
+        let inserted = self
+            .invalid_escapes
+            .iter()
+            .enumerate()
+            .take_while(|(k, idx)| **idx + k < offset)
+            .count();
+        offset - inserted
+    }
+
+    /// 补全需要对修复过的完整json进行时，在已有的扫描状态上接着扫描剩余的部分，
+    /// 这样每次补全都不需要从头扫描：宽松模式下修复过非法转义，或者方言输入的末尾还有尚未确定的部分
+    fn completing(&self) -> Option<Parser<'static>> {
+        let tail = self.dialect_tail();
+        match &self.repaired {
+            Some(repaired) => {
+                Some(repaired.extended(&(self.src_str[self.synced..].to_string() + &tail)))
+            }
+            None if tail.is_empty() => None,
+            None => Some(self.extended(&tail)),
+        }
+    }
+
+    /// 接着扫描`tail`，结果与对`src_str + tail`完整扫描相同
+    fn extended(&self, tail: &str) -> Parser<'static> {
+        let mut parser = Parser {
+            stack: self.stack.clone(),
+            state: self.state.clone(),
+            src_str: Cow::Owned(self.src_str.to_string() + tail),
+            last_sep: self.last_sep,
+            last_colon: self.last_colon,
+            last_rbracket: self.last_rbracket,
+            is_parsed: self.is_parsed.clone(),
+            settings: self.settings.clone().dialect(Dialect::Json),
+            last_escape: self.last_escape,
+            invalid_escapes: self.invalid_escapes.clone(),
+            ..Default::default()
+        };
+        if !parser.is_parsed.is_error() {
+            parser.parse_from(self.src_str.len(), &mut ());
+        }
+        parser
+    }
+
+    /// 把新确定的输入修复之后交给`repaired`，尚未结束的转义要等到它结束
+    fn sync_repaired(&mut self) {
+        if self.invalid_escapes.is_empty() {
+            return;
+        }
+        let end = match &self.state {
+            State::InStr(cnt) if cnt.cnt() > 0 => self.last_escape,
+            _ => self.src_str.len(),
+        };
+        if end <= self.synced {
+            return;
+        }
+        let first = self
+            .invalid_escapes
+            .partition_point(|idx| *idx < self.synced);
+        let mut chunk = String::with_capacity(end - self.synced);
+        let mut last = self.synced;
+        for &idx in &self.invalid_escapes[first..] {
+            chunk.push_str(&self.src_str[last..idx]);
+            chunk.push('\\');
+            last = idx;
+        }
+        chunk.push_str(&self.src_str[last..end]);
+        let repaired = self.repaired.get_or_insert_with(|| {
+            let mut parser = Parser::parser_owned(self.settings.clone().dialect(Dialect::Json));
+            parser.parse();
+            Box::new(parser)
+        });
+        repaired.push_str(&chunk);
+        self.synced = end;
+    }
+
+    /// 宽松模式下，将非法转义中的反斜杠本身转义之后的输入
+    pub(crate) fn repaired_src(&self) -> Option<String> {
+        if self.invalid_escapes.is_empty() {
+            return None;
+        }
+        let mut res = String::with_capacity(self.src_str.len() + self.invalid_escapes.len());
+        let mut last = 0;
+        for &idx in &self.invalid_escapes {
+            res.push_str(&self.src_str[last..idx]);
+            res.push('\\');
+            last = idx;
+        }
+        res.push_str(&self.src_str[last..]);
+        Some(res)
+    }
+
+    pub(crate) fn settings(&self) -> &ParseSettings {
+        &self.settings
+    }
+
+    pub(crate) fn is_parsed(&self) -> &RunState<Error> {
+        &self.is_parsed
+    }
+
+    fn parse_from<V: ScanVisitor>(&mut self, start: usize, visitor: &mut V) {
+        let mut idx = start;
+        while let Some(c) = self.src_str[idx..].chars().next() {
+            let mut char_type = self.state_machine_input(c);
+            if char_type == CharType::InvalidEscape {
+                if self.settings.escape_mode == EscapeMode::Strict {
+                    self.is_parsed = RunState::Error(Error::InvalidEscape {
+                        offset: self.last_escape,
+                    });
+                    return;
+                }
+                // 宽松模式下反斜杠视为普通字符，当前字符需要重新输入
+                log_warn!("repaired invalid escape at {}", self.last_escape);
+                self.invalid_escapes.push(self.last_escape);
+                self.warnings.push(Warning::RepairedEscape {
+                    offset: self.last_escape,
+                });
+                char_type = self.state_machine_input(c);
+            }
+            if char_type == CharType::Escape {
+                self.last_escape = idx;
+            }
             if char_type.is_left_available() {
                 self.stack.push((idx, char_type))
             } else if char_type.is_right_available() {
@@ -273,57 +907,79 @@ impl<'a> Parser<'a> {
                     self.stack.pop();
                 } else {
                     // 栈顶为空或者栈顶元素不匹配，此时应该退出并报错
-                    let remains = format!("remains: {}\n", &self.src_str[idx..]);
-                    self.is_parsed = RunState::Error(remains);
-                    println!("Warning: Stack is empty or its top element is unmatched");
+                    let expected = self
+                        .stack
+                        .last()
+                        .and_then(|(_, t)| t.partial_pair())
+                        .and_then(|t| t.type_string().chars().next());
+                    self.is_parsed = RunState::Error(Error::MismatchedBracket {
+                        offset: idx,
+                        found: c,
+                        expected,
+                    });
+                    log_warn!("unmatched bracket '{}' at {}", c, idx);
                     return;
                 }
             } else if char_type == CharType::Comma {
                 self.last_sep = Some(idx);
             } else if char_type == CharType::Colon {
                 self.last_colon = Some(idx);
-            }
-        }
-    }
-
-    fn stack_recover(&mut self, idx: usize) {
-        while let Some((top_idx, _)) = self.stack.last() {
-            if *top_idx < idx {
-                break;
+            } else if char_type == CharType::Quotation {
+                visitor.visit_quote(&self.src_str, idx);
+                idx += c.len_utf8();
+                continue;
             } else {
-                self.stack.pop();
+                idx += c.len_utf8();
+                continue;
             }
+            visitor.visit(&self.src_str, idx, char_type);
+            idx += c.len_utf8();
+        }
+        self.sync_repaired();
+        // 方言中无法翻译的字面量，之后的输入也不会让它变得合法。
+        // 末尾尚未确定的部分每次都需要重新翻译，因此这里只检查已经确定的输入
+        if let Some(offset) = self
+            .dialect
+            .as_ref()
+            .and_then(|dialect| dialect.committed_error())
+        {
+            self.is_parsed = RunState::Error(Error::Uncompletable { offset });
         }
     }
 
     #[inline]
-    fn cut_and_amend(&mut self, idx: usize, allow_string: bool) -> Result<String, bool> {
-        // error的bool表示是否已经匹配成功，匹配成功但是不完整Err(true)，没有命中返回Err(false)
-
+    pub(crate) fn cut_and_amend(
+        &self,
+        idx: usize,
+        allow_string: bool,
+    ) -> Result<value_parser::VParserRes, Error> {
         // 获取冒号后的字符切片
         let s = &self.src_str[idx..];
         let (s, _) = value_parser::sp(s).unwrap();
+        // 没有命中任何一种值，或者命中了但是不允许残缺时，都视为无法补全
+        let err = Error::Uncompletable {
+            offset: self.src_str.len() - s.len(),
+        };
 
         #[inline]
         // 定义一个通用的解析和校验函数
         fn parse_and_check<F>(
-            _par: &mut Parser,
+            _par: &Parser,
             _idx: usize,
             s: &str,
             parse_func: F,
             allow_incomplete: bool,
-        ) -> Result<(bool, String), ()>
+        ) -> Result<Option<value_parser::VParserRes>, Error>
         where
-            F: Fn(&str) -> Result<value_parser::VParserRes, ()>,
+            F: Fn(&str) -> Result<value_parser::VParserRes, Error>,
         {
-            if let Ok(parse_res) = parse_func(s) {
-                if allow_incomplete || parse_res.is_complete() {
-                    return Ok((true, s.to_string()));
-                } else {
-                    return Ok((false, String::new()));
-                }
+            let parse_res = parse_func(s)?;
+            if allow_incomplete || parse_res.is_complete() {
+                // 使用补全后的值，而不是原始的切片，如"ab -> "ab"，tr -> true
+                Ok(Some(parse_res))
+            } else {
+                Ok(None)
             }
-            Err(())
         }
 
         // 尝试解析bool
@@ -332,7 +988,7 @@ impl<'a> Parser<'a> {
             idx,
             s,
             value_parser::parse_bool,
-            self.settings.allow_bool,
+            self.settings.allows(Allow::BOOL),
         )
         // 如果解析bool失败，尝试解析字符串
         .or_else(|_| {
@@ -341,7 +997,7 @@ impl<'a> Parser<'a> {
                 idx,
                 s,
                 value_parser::parse_string,
-                allow_string && self.settings.allow_string,
+                allow_string && self.settings.allows(Allow::STR),
             )
         })
         .or_else(|_| {
@@ -349,8 +1005,8 @@ impl<'a> Parser<'a> {
                 self,
                 idx,
                 s,
-                value_parser::parse_num,
-                self.settings.allow_number,
+                |s| self.settings.number.parse_num(s),
+                self.settings.allows(Allow::NUM),
             )
         })
         // 如果解析数字失败，尝试解析其它特殊字符
@@ -360,7 +1016,7 @@ impl<'a> Parser<'a> {
                 idx,
                 s,
                 value_parser::parse_nan,
-                self.settings.allow_nan,
+                self.settings.allows(Allow::NAN),
             )
         })
         .or_else(|_| {
@@ -369,7 +1025,7 @@ impl<'a> Parser<'a> {
                 idx,
                 s,
                 value_parser::parse_null,
-                self.settings.allow_null,
+                self.settings.allows(Allow::NULL),
             )
         })
         .or_else(|_| {
@@ -378,7 +1034,7 @@ impl<'a> Parser<'a> {
                 idx,
                 s,
                 value_parser::parse_infinity,
-                self.settings.allow_infinity,
+                self.settings.allows(Allow::INFINITY),
             )
         })
         .or_else(|_| {
@@ -387,135 +1043,182 @@ impl<'a> Parser<'a> {
                 idx,
                 s,
                 value_parser::parse_ninfinity,
-                self.settings.allow_ninfinity,
+                self.settings.allows(Allow::NINFINITY),
             )
         })
-        .or(Err(false))
-        .and_then(|(res, s)| if res { Ok(s) } else { Err(true) })
+        .or(Err(err.clone()))
+        .and_then(|res| res.ok_or(err.clone()))
+        .and_then(|res| {
+            if self.settings.monotonic && !is_settled(s, &res) {
+                Err(err)
+            } else {
+                Ok(res)
+            }
+        })
     }
 
-    #[inline]
-    fn get_recover_idx(&self, colon_idx: Option<usize>) -> Result<usize, ()> {
-        if let Some(colon_idx) = colon_idx {
-            self.stack
-                .iter()
-                .rev()
-                .find(|(idx, _)| *idx < colon_idx)
-                .map(|(idx, _)| *idx + 1)
-                .ok_or(())
-        } else {
-            Ok(self.stack.last().unwrap().0)
+    /// `idx`之后处于key位置的内容，补全为key的字符串；不是字符串或者之后还有其它内容时返回`None`
+    pub(crate) fn partial_key(&self, idx: usize) -> Option<String> {
+        let tail = self.src_str[idx..].trim_start_matches(value_parser::is_space);
+        let res = value_parser::parse_string(tail).ok()?;
+        if res.is_complete() && res.amend_value() != tail.trim_end_matches(value_parser::is_space) {
+            return None;
         }
+        Some(res.into_amend_value())
     }
 
-    fn get_is_amend(&self, sep_idx: Option<usize>) -> Option<bool> {
-        // 最新的，当前','之前的括号决定了这个组是obj还是arr
-        // 如果这个括号过时了呢？应该找最新的符号的
-        if let Some(sep_idx) = sep_idx {
-            self.stack
-                .iter()
-                .rev()
-                .find(|(idx, _)| *idx < sep_idx)
-                .map(|(_, c)| *c == CharType::LCB)
-        } else {
-            self.stack.last().map(|(_, c)| *c == CharType::LCB)
+    fn empty_value(&self, err: Error) -> Result<(String, usize), Error> {
+        match &self.settings.empty_value {
+            Some(value) => Ok((value.clone(), 0)),
+            None => Err(err),
         }
     }
 
-    fn amend(&mut self) -> Result<String, ()> {
-        assert!(self.is_parsed.is_not_none());
-        if self.is_parsed.is_error() {
-            return Err(());
+    fn amend(&self) -> Result<String, Error> {
+        self.amend_with_pending().map(|(s, _)| s)
+    }
+
+    /// 除了补全后的字符串之外，还返回其中"尚未确定"部分的起始位置：
+    /// 在此之前的内容都来自已经结束的值，之后的是残缺的值以及补上的括号
+    pub(crate) fn amend_with_pending(&self) -> Result<(String, usize), Error> {
+        if self.is_parsed.is_none() {
+            // 还没有调用`parse`时在副本上扫描，parser本身保持不变
+            let mut parser = Parser {
+                src_str: Cow::Borrowed(&self.src_str),
+                settings: self.settings.clone(),
+                dialect: self.dialect.clone(),
+                ..Default::default()
+            };
+            parser.parse();
+            return parser.amend_with_pending();
+        } else if let RunState::Error(err) = &self.is_parsed {
+            return Err(err.clone());
+        } else if let Some(err) = self.dialect_error() {
+            return Err(err);
+        } else if let Some(parser) = self.completing() {
+            return parser
+                .amend_with_pending()
+                .map_err(|err| err.map_offset(|offset| self.unrepaired_offset(offset)));
         } else if self.is_parsed.is_success() && self.stack.is_empty() {
+            if self.src_str.trim_matches(value_parser::is_space).is_empty() {
+                return self.empty_value(Error::Empty);
+            }
             match self.cut_and_amend(0, true) {
-                Ok(res) => return Ok(res),
-                Err(_) => {
+                Ok(res) => {
+                    let pending = if res.is_complete() {
+                        res.amend_value().len()
+                    } else {
+                        0
+                    };
+                    return Ok((res.into_amend_value(), pending));
+                }
+                Err(err) => {
                     if self.last_rbracket.is_some() {
                         // 说明曾经存在括号
-                        return Ok(self.src_str.to_string());
+                        return Ok((self.src_str.to_string(), self.src_str.len()));
                     } else {
-                        return Err(());
+                        // 根节点的原子值无法补全时被丢弃，与空输入相同
+                        return self.empty_value(err);
                     }
                 }
             }
         }
 
-        let mut cur_string = String::new();
-        let valid_idx: Option<i128>;
-        let mut amend_system: Option<bool> = None; // false对应[, true对应{
-        let recover_idx: usize; // 用于恢复的idx，仅当需要恢复时使用
-        let top_elem = self.stack.last();
+        // 存在不允许残缺的数组或对象时，从最外层的这个括号处截断后重新补全
+        if let Some(pos) = self
+            .stack
+            .iter()
+            .position(|(_, c)| !self.settings.allows_collection(*c))
+        {
+            if pos == 0 {
+                return Err(Error::Uncompletable {
+                    offset: self.stack[0].0,
+                });
+            }
+            let mut parser = Parser {
+                src_str: self.src_str[..self.stack[pos].0].into(),
+                settings: self.settings.clone(),
+                ..Default::default()
+            };
+            parser.parse();
+            return parser.amend_with_pending();
+        }
 
-        let last_rbracket = self.last_rbracket;
+        // amend不修改parser本身，这样流式解析时可以在任意时刻补全。
+        // 最后一个结构字符决定了之后的内容是值还是key，无法补全的值连同它的key以及逗号一起丢弃
+        let (top_idx, top) = *self.stack.last().unwrap();
+        let last = [
+            Some(top_idx),
+            self.last_sep,
+            self.last_colon,
+            self.last_rbracket,
+        ]
+        .into_iter()
+        .flatten()
+        .max()
+        .unwrap_or(top_idx);
+        let in_object = top == CharType::LCB;
+        let is_value = match &self.src_str[last..last + 1] {
+            ":" => true,
+            "," | "[" | "{" => !in_object,
+            // 内层的数组或对象刚刚闭合，之后只有空白
+            _ => false,
+        };
+        let atom = if is_value {
+            self.cut_and_amend(last + 1, true).ok()
+        } else {
+            None
+        };
 
-        // 注意，目前这里存储的所有都是字节序
-        if let Some(last_colon) = self.last_colon {
-            if let Some(last_sep) = self.last_sep {
-                valid_idx = if last_colon > last_sep {
-                    Some(last_colon as i128)
-                } else {
-                    assert!(last_colon != last_sep);
-                    amend_system = self.get_is_amend(Some(self.last_sep.unwrap()));
-                    Some(last_sep as i128)
-                };
-                recover_idx = last_sep;
+        // 对象中缺少值的key按照设置保留，并补上占位值
+        let key = match self.settings.key_placeholder() {
+            Some(placeholder) if in_object && atom.is_none() => {
+                match &self.src_str[last..last + 1] {
+                    ":" => Some(placeholder),
+                    "," | "{" => self
+                        .partial_key(last + 1)
+                        .map(|key| format!("{}:{}", key, placeholder)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        let mut cur_string;
+        let pending;
+        if let Some(res) = atom {
+            cur_string = self.src_str[..=last].to_string();
+            pending = if res.is_complete() {
+                cur_string.len() + res.amend_value().len()
             } else {
-                valid_idx = Some(last_colon as i128);
-                // 此时使用top_elem来recover有可能出错，如[{"":[
-                // recover_idx = top_elem.map_or(1, |(i, _)| i + 1);
-                recover_idx = self.get_recover_idx(Some(last_colon))?
-            }
-        } else if let Some(last_sep) = self.last_sep {
-            amend_system = self.get_is_amend(Some(last_sep));
-            valid_idx = Some(last_sep as i128);
-            recover_idx = last_sep;
+                cur_string.len()
+            };
+            cur_string.push_str(res.amend_value());
+        } else if let Some(key) = key {
+            cur_string = self.src_str[..=last].to_string();
+            pending = cur_string.len();
+            cur_string.push_str(&key);
         } else {
-            amend_system = self.get_is_amend(None);
-            // 如果是null的话，这个是对的么？
-            valid_idx = top_elem.map_or(Some(-1), |(i, _)| Some(*i as i128));
-            // 这时候使用top_elem作为recovery应该不会出错
-            recover_idx = top_elem.map_or(0, |(i, _)| *i);
-        }
-
-        if let Some(valid_idx) = valid_idx {
-            let last_rbracket = last_rbracket.map_or(valid_idx, |i| i as i128);
-
-            // 外部需要保证len不为0
-            if valid_idx == (self.src_str.len() - 1) as i128 {
-                self.stack_recover(recover_idx);
-                cur_string.push_str(&self.src_str[..recover_idx]);
-            } else if last_rbracket <= valid_idx {
-                let keyval_only = amend_system.map_or(false, |c| c);
-                if !keyval_only {
-                    if let Ok(s) = self.cut_and_amend((valid_idx + 1) as usize, keyval_only) {
-                        cur_string.push_str(&self.src_str[..(valid_idx + 1) as usize]);
-                        cur_string.push_str(&s);
-                    } else {
-                        // 此时cut_and_amend匹配失败，因此需要进行恢复
-                        self.stack_recover(recover_idx);
-                        cur_string.push_str(&self.src_str[..recover_idx]);
+            let cut = match &self.src_str[last..last + 1] {
+                "," => last,
+                ":" => {
+                    // key之前的逗号或者左括号
+                    match self.last_sep.filter(|sep| *sep > top_idx && *sep < last) {
+                        Some(sep) => sep,
+                        None => top_idx + 1,
                     }
-                } else {
-                    // 此时只匹配key_val，因此需要进行恢复
-                    self.stack_recover(recover_idx);
-                    cur_string.push_str(&self.src_str[..recover_idx]);
                 }
-            } else {
-                cur_string.push_str(&self.src_str[..=last_rbracket as usize]);
-            }
-        } else {
-            return Err(());
+                _ => last + 1,
+            };
+            cur_string = self.src_str[..cut].to_string();
+            pending = cur_string.len();
         }
 
         for (_, c) in self.stack.iter().rev() {
             let s = CharType::option_type_string(c.partial_pair());
             cur_string.push_str(&s);
         }
-        if cur_string.is_empty() {
-            Err(())
-        } else {
-            Ok(cur_string)
-        }
+        Ok((cur_string, pending))
     }
 
     fn state_machine_input(&mut self, c: char) -> CharType {
@@ -537,6 +1240,15 @@ impl<'a> Parser<'a> {
     }
 }
 
+/// 原子值在之后的输入中是否还会改变：残缺的字符串只会变长，完整的关键字不会再变，
+/// 数字必须原样出现并且后面已经跟着空白
+fn is_settled(s: &str, res: &value_parser::VParserRes) -> bool {
+    let atom = s.trim_end_matches(value_parser::is_space);
+    res.is_complete()
+        || res.amend_value().starts_with('"')
+        || (res.amend_value() == atom && atom.len() < s.len())
+}
+
 #[cfg(test)]
 mod test {
     use crate::test_utils::{arb_json, Tester};
@@ -571,7 +1283,7 @@ mod test {
 
     fn parser_full_pass(s: &str) -> Result<(), String> {
         let mut parser = Parser {
-            src_str: s,
+            src_str: s.into(),
             ..Default::default()
         };
         parser.parse();
@@ -587,7 +1299,7 @@ mod test {
         } else {
             Ok(())
         };
-        res.map_err(|err| "\n".to_string() + &err + &parser.parse_tracer())
+        res.map_err(|err| "\n".to_string() + &err + &parser.parse_tracer().to_string())
     }
 
     #[test]
@@ -604,7 +1316,7 @@ mod test {
         fn parser_test_full_pass_prop(s in arb_json()) {
             let s = s.to_string();
             let mut parser = Parser {
-                src_str: &s,
+                src_str: s.as_str().into(),
                 ..Default::default()
             };
             parser.parse();
@@ -628,7 +1340,7 @@ mod test {
                     continue;
                 }
                 let mut parser = Parser {
-                    src_str: &s[..i],
+                    src_str: s[..i].into(),
                     ..Default::default()
                 };
                 parser.parse();
@@ -710,7 +1422,7 @@ mod test {
                     continue;
                 }
                 let mut parser = Parser {
-                    src_str: &s[..i],
+                    src_str: s[..i].into(),
                     ..Default::default()
                 };
                 parser.parse();
@@ -723,6 +1435,623 @@ mod test {
         }
     }
 
+    #[test]
+    fn parser_with_allow() {
+        // 完整的值总是保留
+        assert_eq!(
+            Parser::parser_with("[true, null", Allow::ARR).unwrap(),
+            "[true,null]"
+        );
+        // 残缺的数字只有在允许时才保留
+        assert_eq!(Parser::parser_with("[1, 23", Allow::ARR).unwrap(), "[1]");
+        assert_eq!(
+            Parser::parser_with("[1, 23", Allow::ARR | Allow::NUM).unwrap(),
+            "[1,23]"
+        );
+        // 不允许残缺的数组时，整个数组连同它的key都会被截掉
+        assert_eq!(
+            Parser::parser_with(r#"{"a": [1, 2"#, Allow::OBJ | Allow::NUM).unwrap(),
+            "{}"
+        );
+        assert_eq!(
+            Parser::parser_with(r#"[1, {"a": 2"#, Allow::ARR | Allow::NUM).unwrap(),
+            "[1]"
+        );
+        assert!(Parser::parser_with("[1, 2", Allow::OBJ | Allow::NUM).is_err());
+        // 完整的数组不受影响
+        assert_eq!(
+            Parser::parser_with(r#"{"a": [1, 2], "b"#, Allow::OBJ).unwrap(),
+            r#"{"a": [1, 2]}"#
+        );
+    }
+
+    #[test]
+    fn parser_with_amend_value() {
+        let cases = [
+            (r#"["ab"#, r#"["ab"]"#),
+            (r#"["ab\u00"#, r#"["ab"]"#),
+            ("[tr", "[true]"),
+            ("[1, 12.", "[1,12]"),
+            (r#"{"a": nu"#, r#"{"a":null}"#),
+            (r#"{"a": -Inf"#, r#"{"a":-Infinity}"#),
+            (r#""abc"#, r#""abc""#),
+            ("nu", "null"),
+            ("  fa", "false"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(Parser::parser_with(input, Allow::ALL).unwrap(), expected);
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(256))]
+        #[test]
+        fn parser_test_pass_prop_allow_all(s in arb_json()) {
+            let s = s.to_string();
+            for (i, _) in s.char_indices().skip(1) {
+                if let Ok(res) = Parser::parser_with(&s[..i], Allow::ALL) {
+                    if !is_valid_json(&res) {
+                        panic!("failed_str: {:?}, res: {:?}", &s[..i], res);
+                    }
+                }
+            }
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(512))]
+        #[test]
+        fn parser_no_panic_prop(s in r#"[\[\]{}",:\\ux0-9a-fG ]{0,24}"#) {
+            let lenient = ParseSettings::new()
+                .allow(Allow::ALL)
+                .escape_mode(EscapeMode::Lenient);
+            for (i, _) in s.char_indices().chain([(s.len(), ' ')]) {
+                let _ = Parser::parser(&s[..i]);
+                let _ = Parser::parser_with(&s[..i], Allow::ALL);
+                let _ = Parser::parser_with(&s[..i], lenient.clone());
+                let _ = crate::loads(&s[..i], lenient.clone());
+                let _ = crate::from_partial_str::<Value>(&s[..i]);
+                let mut streaming = crate::StreamingParser::with_settings(lenient.clone());
+                streaming.feed(&s[..i]);
+                let _ = streaming.complete();
+                let json5 = ParseSettings::new().allow(Allow::ALL).dialect(Dialect::Json5);
+                let _ = Parser::parser_with(&s[..i], json5.clone().keep_dialect(true));
+                let _ = crate::loads(&s[..i], json5.clone());
+                let _ = crate::loads(&s[..i], json5.dialect(Dialect::PythonLiteral));
+            }
+        }
+
+        #[test]
+        fn parser_total_prefix_prop(s in arb_json(), all in any::<bool>()) {
+            // 合法json的任意前缀都能补全为合法的json，并且与loads的结果一致；
+            // 默认设置下截掉的原子值由`empty_value`兜底
+            let s = s.to_string();
+            let allow = if all { Allow::ALL } else { Allow::COLLECTION };
+            let settings = ParseSettings::new().allow(allow).empty_value(Value::Null);
+            for (i, _) in s.char_indices().chain([(s.len(), ' ')]) {
+                let res = Parser::parser_with(&s[..i], settings.clone());
+                prop_assert!(res.is_ok(), "input: {:?}, res: {:?}", &s[..i], res);
+                let res = res.unwrap();
+                prop_assert!(is_valid_json(&res), "input: {:?}, res: {:?}", &s[..i], res);
+                prop_assert_eq!(
+                    crate::loads(&s[..i], settings.clone()),
+                    crate::loads(&res, settings.clone()),
+                    "input: {:?}, res: {:?}", &s[..i], res
+                );
+            }
+        }
+
+        #[test]
+        fn parser_json5_superset_prop(s in arb_json()) {
+            // json本身就是合法的JSON5，两种方言的补全结果应该相同，
+            // 只是JSON5的结果总是严格的json，字符串中的控制字符会被转义
+            let s = s.to_string();
+            let json5 = ParseSettings::new().allow(Allow::ALL).dialect(Dialect::Json5);
+            for (i, _) in s.char_indices().skip(1) {
+                prop_assert_eq!(
+                    Parser::parser_with(&s[..i], json5.clone()),
+                    Parser::parser_with(&s[..i], Allow::ALL).map(|res| escape_controls(&res)),
+                    "input: {:?}", &s[..i]
+                );
+            }
+        }
+
+        #[test]
+        fn parser_monotonic_prop(s in arb_json()) {
+            // 单调模式下每个前缀的结果都是上一个结果的延伸，一旦出现结果就不会再消失
+            let settings = ParseSettings::new().allow(Allow::ALL).monotonic(true);
+            for s in [s.to_string(), serde_json::to_string_pretty(&s).unwrap()] {
+                let mut last: Option<Value> = None;
+                for (i, _) in s.char_indices().skip(1).chain([(s.len(), ' ')]) {
+                    let res = Parser::parser_with(&s[..i], settings.clone());
+                    let value = match (res, &last) {
+                        (Ok(res), _) => crate::loads(&res, Allow::ALL).unwrap(),
+                        (Err(_), None) => continue,
+                        (Err(err), Some(_)) => {
+                            return Err(TestCaseError::fail(format!("input: {:?}, err: {:?}", &s[..i], err)));
+                        }
+                    };
+                    if let Some(last) = &last {
+                        prop_assert!(
+                            extends(last, &value),
+                            "input: {:?}, {} -> {}", &s[..i], last, value
+                        );
+                    }
+                    last = Some(value);
+                }
+            }
+        }
+
+        #[test]
+        fn parser_partial_key_prop(s in arb_json()) {
+            // 保留缺少值的key时，补全结果仍然合法并且与loads的结果一致
+            let s = s.to_string();
+            let settings = ParseSettings::new()
+                .allow(Allow::ALL)
+                .empty_value(Value::Null)
+                .partial_key(PartialKeyPolicy::KeepWithNull);
+            for (i, _) in s.char_indices().skip(1) {
+                let res = Parser::parser_with(&s[..i], settings.clone());
+                prop_assert!(res.is_ok(), "input: {:?}, res: {:?}", &s[..i], res);
+                let res = res.unwrap();
+                prop_assert!(is_valid_json(&res), "input: {:?}, res: {:?}", &s[..i], res);
+                prop_assert_eq!(
+                    crate::loads(&s[..i], settings.clone()),
+                    crate::loads(&res, settings.clone()),
+                    "input: {:?}, res: {:?}", &s[..i], res
+                );
+            }
+        }
+    }
+
+    /// 转义控制字符，输入中没有结构上的空白时只会出现在字符串中
+    fn escape_controls(s: &str) -> String {
+        s.chars()
+            .map(|c| match c {
+                '\n' => "\\n".to_string(),
+                '\r' => "\\r".to_string(),
+                '\t' => "\\t".to_string(),
+                '\u{8}' => "\\b".to_string(),
+                '\u{c}' => "\\f".to_string(),
+                _ if c < '\u{20}' => format!("\\u{:04x}", c as u32),
+                _ => c.to_string(),
+            })
+            .collect()
+    }
+
+    /// `new`是否是`old`在结构上的延伸
+    fn extends(old: &Value, new: &Value) -> bool {
+        match (old, new) {
+            (Value::Array(old), Value::Array(new)) => {
+                old.len() <= new.len() && old.iter().zip(new).all(|(o, n)| extends(o, n))
+            }
+            (Value::Object(old), Value::Object(new)) => old
+                .iter()
+                .all(|(k, o)| new.get(k).is_some_and(|n| extends(o, n))),
+            (Value::String(old), Value::String(new)) => new.starts_with(old.as_str()),
+            _ => old == new,
+        }
+    }
+
+    #[test]
+    fn parser_non_finite() {
+        let settings = ParseSettings::new().allow(Allow::ALL);
+        let src = r#"{"NaN": "-Infinity", "a": [NaN, Infinity, 1], "b": -Inf"#;
+        let cases = [
+            (
+                NonFinitePolicy::Preserve,
+                Ok(r#"{"NaN": "-Infinity", "a": [NaN, Infinity, 1], "b":-Infinity}"#),
+            ),
+            (
+                NonFinitePolicy::Null,
+                Ok(r#"{"NaN": "-Infinity", "a": [null, null, 1], "b":null}"#),
+            ),
+            (
+                NonFinitePolicy::String,
+                Ok(r#"{"NaN": "-Infinity", "a": ["NaN", "Infinity", 1], "b":"-Infinity"}"#),
+            ),
+            (NonFinitePolicy::Error, Err(Error::NonFinite { offset: 27 })),
+        ];
+        for (policy, expected) in cases {
+            let res = Parser::parser_with(src, settings.clone().non_finite(policy));
+            assert_eq!(res.as_deref().map_err(Clone::clone), expected);
+        }
+
+        // 补全的详细信息中也要处理，保留的前缀在被替换的值之前结束
+        let res = Parser::completion_with(
+            "[1, NaN, 2",
+            settings.clone().non_finite(NonFinitePolicy::Null),
+        )
+        .unwrap();
+        assert_eq!(res.text, "[1, null,2]");
+        assert_eq!(res.kept_prefix_len, 4);
+        assert_eq!(res.suffix, "null,2]");
+        let mut streaming = crate::StreamingParser::with_settings(
+            settings.clone().non_finite(NonFinitePolicy::String),
+        );
+        streaming.feed("[Infinity, [-Inf");
+        assert_eq!(
+            streaming.completion().unwrap().text,
+            r#"["Infinity", ["-Infinity"]]"#
+        );
+        let res =
+            Parser::completion_with("[NaN", settings.clone().non_finite(NonFinitePolicy::Error));
+        assert_eq!(res, Err(Error::NonFinite { offset: 1 }));
+
+        // JSON5保留方言时，单引号字符串、注释以及未加引号的key都不受影响
+        let json5 = settings.dialect(Dialect::Json5).keep_dialect(true);
+        let src = "{NaN: 'NaN', /* NaN */ b: [NaN, 'x\\'', Infinity";
+        let res = Parser::parser_with(src, json5.clone().non_finite(NonFinitePolicy::Null));
+        assert_eq!(
+            res.unwrap(),
+            r#"{NaN: 'NaN', /* NaN */ b: [null, "x'",null]}"#
+        );
+        let res = Parser::parser_with(src, json5.non_finite(NonFinitePolicy::Error));
+        assert_eq!(res, Err(Error::NonFinite { offset: 27 }));
+    }
+
+    #[test]
+    fn parser_python_literal() {
+        let python = ParseSettings::new()
+            .allow(Allow::ALL)
+            .dialect(Dialect::PythonLiteral)
+            // 只对JSON5有效
+            .keep_dialect(true);
+        let src = r#"{'name': "it's", 'ok': True, 'v': None, 't': (1, 2,), 'r': r'\d', 'p': b'\x41\q', 'n': Fa"#;
+        let res = Parser::parser_with(src, python.clone()).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&res).unwrap(),
+            serde_json::json!({
+                "name": "it's", "ok": true, "v": null, "t": [1, 2],
+                "r": "\\d", "p": "A\\q", "n": false
+            })
+        );
+        assert_eq!(
+            Parser::parser_with("[1_000, (), 'a\\U0001F600\\101', No", python.clone()).unwrap(),
+            "[1000, [], \"a\u{1F600}\\u0041\",null]"
+        );
+        let value = crate::loads("{1: [Tr", python.clone()).unwrap();
+        assert_eq!(value, serde_json::json!({"1": [true]}));
+
+        // 不支持的字面量返回错误，offset是原始输入中的位置
+        let err = Error::Uncompletable { offset: 12 };
+        assert_eq!(
+            Parser::parser_with("[1e5, -0.5, 1j]", python.clone()),
+            Err(err.clone())
+        );
+        assert_eq!(crate::loads("[1e5, -0.5, 1j]", python.clone()), Err(err));
+        // Python中没有不带引号的key，JSON5中才有
+        assert_eq!(
+            crate::loads("{'a': 1, abc: 2}", python.clone()),
+            Err(Error::Uncompletable { offset: 9 })
+        );
+        let json5 = python.clone().dialect(Dialect::Json5);
+        assert_eq!(
+            crate::loads("{'a': 1, abc: 2}", json5).unwrap(),
+            serde_json::json!({"a": 1, "abc": 2})
+        );
+        assert_eq!(
+            crate::loads("{True: 1, 2.5: None}", python.clone()).unwrap(),
+            serde_json::json!({"true": 1, "2.5": null})
+        );
+        assert_eq!(
+            Parser::parser_with("('''a\nb''', (1))", python).unwrap(),
+            "[\"a\\nb\", 1]"
+        );
+    }
+
+    #[test]
+    fn parser_json5() {
+        let json5 = ParseSettings::new()
+            .allow(Allow::ALL)
+            .dialect(Dialect::Json5);
+        let src = "{name: 'x', /* c */ list: [0x10, .5, +1,], // c\n s: 'a\\\nb', t: tr";
+        let res = Parser::parser_with(src, json5.clone()).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&res).unwrap(),
+            serde_json::json!({"name": "x", "list": [16, 0.5, 1], "s": "ab", "t": true})
+        );
+        assert_eq!(
+            Parser::parser_with("{a: 'x", json5.clone()).unwrap(),
+            r#"{"a":"x"}"#
+        );
+        // 保留原本的写法，只补上缺失的部分
+        let keep = json5.clone().keep_dialect(true);
+        assert_eq!(
+            Parser::parser_with("{a: 'x', b: [1, 2,", keep.clone()).unwrap(),
+            "{a: 'x', b: [1, 2]}"
+        );
+        assert_eq!(
+            Parser::parser_with("// c\n[0x1F, 'y", keep).unwrap(),
+            "// c\n[0x1F,\"y\"]"
+        );
+        let value = crate::loads("[Infinity, -0x10, 'a\\'b']", json5.clone()).unwrap();
+        assert_eq!(value[1], serde_json::json!(-16));
+        assert_eq!(value[2], serde_json::json!("a'b"));
+        // 字符串中的控制字符被转义，结果仍然是严格的json
+        let res = Parser::parser_with("['a\tb', '\x01', 1", json5).unwrap();
+        assert_eq!(res, r#"["a\tb", "\u0001",1]"#);
+    }
+
+    #[test]
+    fn parser_monotonic() {
+        let settings = ParseSettings::new().allow(Allow::ALL).monotonic(true);
+        let cases = [
+            ("[1, 2", "[1]"),
+            ("[1, 2 ", "[1,2]"),
+            (r#"{"a": tr"#, "{}"),
+            (r#"{"a": true"#, r#"{"a":true}"#),
+            (r#"{"a": "x", "b": 1.5"#, r#"{"a": "x"}"#),
+            (r#"["ab"#, r#"["ab"]"#),
+            ("[-Inf", "[]"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                Parser::parser_with(input, settings.clone()).unwrap(),
+                expected,
+                "input: {:?}",
+                input
+            );
+        }
+        // 根节点的数字还可能变长
+        assert!(Parser::parser_with("12", settings.clone()).is_err());
+        assert_eq!(crate::loads("12 ", settings).unwrap(), Value::from(12));
+    }
+
+    #[test]
+    fn parser_partial_key() {
+        let null = ParseSettings::new().partial_key(PartialKeyPolicy::KeepWithNull);
+        let cases = [
+            (r#"{"a":1,"na"#, r#"{"a":1,"na":null}"#),
+            (r#"{"name":"#, r#"{"name":null}"#),
+            (r#"{"name" "#, r#"{"name":null}"#),
+            (r#"{"a": 1, "b\u00"#, r#"{"a": 1,"b":null}"#),
+            (r#"[{"a": [1, 2"#, r#"[{"a": [1]}]"#),
+            ("{", "{}"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                Parser::parser_with(input, null.clone()).unwrap(),
+                expected,
+                "input: {:?}",
+                input
+            );
+        }
+        // 不允许残缺的值也会被替换为占位值
+        let placeholder = ParseSettings::new().allow(Allow::OBJ).partial_key(
+            PartialKeyPolicy::KeepWithPlaceholder(serde_json::json!("…")),
+        );
+        assert_eq!(
+            Parser::parser_with(r#"{"a": "x", "b": [1"#, placeholder.clone()).unwrap(),
+            r#"{"a": "x", "b":"…"}"#
+        );
+        assert_eq!(
+            crate::loads(r#"{"a": "x", "b": [1"#, placeholder).unwrap(),
+            serde_json::json!({"a": "x", "b": "…"})
+        );
+        // 占位值不是字符串时，loads同样按照完整的json构造
+        for value in [
+            serde_json::json!(0),
+            serde_json::json!(true),
+            serde_json::json!({"x": [1]}),
+        ] {
+            let settings = ParseSettings::new()
+                .partial_key(PartialKeyPolicy::KeepWithPlaceholder(value.clone()));
+            let res = Parser::parser_with(r#"{"a": 1, "b"#, settings.clone()).unwrap();
+            assert_eq!(res, format!(r#"{{"a": 1,"b":{value}}}"#));
+            assert_eq!(
+                crate::loads(r#"{"a": 1, "b"#, settings).unwrap(),
+                serde_json::json!({"a": 1, "b": value})
+            );
+        }
+        // 单调模式下占位值之后会变化，因此不生效
+        let monotonic = null.monotonic(true);
+        assert_eq!(Parser::parser_with(r#"{"a":"#, monotonic).unwrap(), "{}");
+    }
+
+    #[test]
+    fn parser_number_policy() {
+        let cases = [
+            ("[1, 12.", ["[1]", "[1,12]", "[1]"]),
+            (r#"{"a": 1e-"#, ["{}", r#"{"a":1}"#, "{}"]),
+            ("[1.5E", ["[]", "[1.5]", "[]"]),
+            ("[12", ["[12]", "[12]", "[]"]),
+            ("[12 ", ["[12]", "[12]", "[12]"]),
+            ("[-", ["[]", "[]", "[]"]),
+        ];
+        let policies = [
+            NumberPolicy::Drop,
+            NumberPolicy::LongestPrefix,
+            NumberPolicy::Delimited,
+        ];
+        for (input, expected) in cases {
+            for (policy, expected) in policies.into_iter().zip(expected) {
+                let settings = ParseSettings::new().allow(Allow::ALL).number(policy);
+                assert_eq!(
+                    Parser::parser_with(input, settings).unwrap(),
+                    expected,
+                    "input: {:?}, policy: {:?}",
+                    input,
+                    policy
+                );
+            }
+        }
+        // 根节点的数字
+        let settings = ParseSettings::new().allow(Allow::ALL);
+        assert_eq!(Parser::parser_with("1e-", settings.clone()).unwrap(), "1");
+        assert!(Parser::parser_with("-", settings.clone()).is_err());
+        let delimited = settings.number(NumberPolicy::Delimited);
+        assert!(Parser::parser_with("12", delimited.clone()).is_err());
+        assert_eq!(crate::loads("12 ", delimited).unwrap(), Value::from(12));
+    }
+
+    #[test]
+    fn parser_total() {
+        let cases = [
+            ("[", "[]"),
+            ("{", "{}"),
+            (" [ ", " []"),
+            (r#"{"a": ["#, r#"{"a": []}"#),
+            (r#"{"a": {"b": ["#, r#"{"a": {"b": []}}"#),
+            ("[1, [", "[1, []]"),
+            (r#"[{"a": 1}, {"b""#, r#"[{"a": 1}, {}]"#),
+            (r#"{"a": [1, 2], "b":"#, r#"{"a": [1, 2]}"#),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                Parser::parser(input).unwrap(),
+                expected,
+                "input: {:?}",
+                input
+            );
+        }
+        assert_eq!(
+            Parser::parser_with(r#"{"a": [1"#, Allow::ALL).unwrap(),
+            r#"{"a": [1]}"#
+        );
+        assert_eq!(Parser::parser(" \n"), Err(Error::Empty));
+        let settings = ParseSettings::new()
+            .allow(Allow::ALL)
+            .empty_value(Value::Null);
+        assert_eq!(Parser::parser_with("", settings.clone()).unwrap(), "null");
+        assert_eq!(Parser::parser_with(" ", settings.clone()).unwrap(), "null");
+        // 根节点的原子值无法补全时与空输入相同
+        assert_eq!(Parser::parser_with("-", settings.clone()).unwrap(), "null");
+        assert_eq!(crate::loads(" ", settings).unwrap(), Value::Null);
+        // 默认设置下根节点的数字也会被截掉
+        assert_eq!(
+            Parser::parser("123"),
+            Err(Error::Uncompletable { offset: 0 })
+        );
+        let settings = ParseSettings::new()
+            .dialect(Dialect::PythonLiteral)
+            .empty_value(serde_json::json!({"a": [0]}));
+        assert_eq!(
+            Parser::parser_with("12", settings.clone()).unwrap(),
+            r#"{"a":[0]}"#
+        );
+        assert_eq!(
+            crate::loads(" ", settings).unwrap(),
+            serde_json::json!({"a": [0]})
+        );
+    }
+
+    #[test]
+    fn parser_complete_without_parse() {
+        let parser = Parser::with_settings(r#"{"a": [1, "x"#, Allow::ALL);
+        assert_eq!(parser.complete().unwrap(), r#"{"a": [1,"x"]}"#);
+        assert_eq!(parser.completion().unwrap().suffix, r#""x"]}"#);
+        let parser = Parser::with_settings("{a: 'x", ParseSettings::new().dialect(Dialect::Json5));
+        assert_eq!(parser.complete().unwrap(), "{}");
+    }
+
+    #[test]
+    fn parser_errors() {
+        assert_eq!(Parser::parser(""), Err(Error::Empty));
+        assert_eq!(
+            Parser::parser("[1, 2}"),
+            Err(Error::MismatchedBracket {
+                offset: 5,
+                found: '}',
+                expected: Some(']'),
+            })
+        );
+        assert_eq!(
+            Parser::parser(r#"{"a": 1}]"#),
+            Err(Error::MismatchedBracket {
+                offset: 8,
+                found: ']',
+                expected: None,
+            })
+        );
+        let drop_num = ParseSettings::new()
+            .allow(Allow::ALL)
+            .number(NumberPolicy::Drop);
+        assert_eq!(
+            Parser::parser_with("  1e", drop_num),
+            Err(Error::Uncompletable { offset: 2 })
+        );
+        assert_eq!(
+            Parser::parser_with(" [1, 2", Allow::OBJ),
+            Err(Error::Uncompletable { offset: 1 })
+        );
+
+        // 方言输入的offset是原始输入中的位置
+        let json5 = ParseSettings::new()
+            .allow(Allow::ALL)
+            .dialect(Dialect::Json5);
+        let src = "{/* long comment */ a: [1, 2}";
+        let err = Error::MismatchedBracket {
+            offset: 28,
+            found: '}',
+            expected: Some(']'),
+        };
+        assert_eq!(Parser::parser_with(src, json5.clone()), Err(err.clone()));
+        assert_eq!(crate::loads(src, json5.clone()), Err(err));
+        assert_eq!(
+            Parser::parser_with("[0x10, 'x'] /* c */ ]", json5),
+            Err(Error::MismatchedBracket {
+                offset: 20,
+                found: ']',
+                expected: None,
+            })
+        );
+    }
+
+    #[test]
+    fn parser_invalid_escape() {
+        assert_eq!(
+            Parser::parser_with(r#"["ab\x41"#, Allow::ALL),
+            Err(Error::InvalidEscape { offset: 4 })
+        );
+        assert_eq!(
+            Parser::parser_with(r#"{"a": "\u12G4"}"#, Allow::ALL),
+            Err(Error::InvalidEscape { offset: 7 })
+        );
+        assert_eq!(
+            crate::value_parser::parse_string(r#""\q"#),
+            Err(Error::InvalidEscape { offset: 1 })
+        );
+
+        let lenient = ParseSettings::new()
+            .allow(Allow::ALL)
+            .escape_mode(EscapeMode::Lenient);
+        assert_eq!(
+            Parser::parser_with(r#"["ab\x41"#, lenient.clone()).unwrap(),
+            r#"["ab\\x41"]"#
+        );
+        assert_eq!(
+            Parser::parser_with(r#"{"a": "\u12G4"}"#, lenient.clone()).unwrap(),
+            r#"{"a": "\\u12G4"}"#
+        );
+        assert_eq!(
+            crate::loads(r#"["\x", "\u1\n"#, lenient).unwrap(),
+            serde_json::json!(["\\x", "\\u1\n"])
+        );
+    }
+
+    #[test]
+    fn parser_trace() {
+        let mut parser = Parser::with_settings(
+            r#"{"a": ["x\q"#,
+            ParseSettings::new().escape_mode(EscapeMode::Lenient),
+        );
+        parser.parse();
+        assert_eq!(parser.warnings(), [Warning::RepairedEscape { offset: 9 }]);
+        let trace = parser.parse_tracer();
+        assert_eq!(trace.stack, [(0, '{'), (6, '[')]);
+        assert!(trace.in_string);
+        assert_eq!(trace.last_colon, Some(4));
+        assert_eq!(trace.error, None);
+
+        let mut parser = Parser::with_settings("[1, 2}", Allow::ALL);
+        parser.parse();
+        let trace = parser.parse_tracer();
+        assert_eq!(trace.last_rbracket, Some(5));
+        assert!(matches!(trace.error, Some(Error::MismatchedBracket { .. })));
+        assert!(parser.warnings().is_empty());
+    }
+
     #[test]
     fn amend_test_full_pass() {
         let list = [
@@ -747,7 +2076,7 @@ mod test {
             }
             println!("{}", s);
             let mut parser = Parser {
-                src_str: s,
+                src_str: s.into(),
                 ..Default::default()
             };
             parser.parse();
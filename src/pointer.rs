@@ -0,0 +1,59 @@
+/// 扫描过程中当前值所在的位置，可以转换为 JSON Pointer
+#[derive(Default, Debug, Clone)]
+pub(crate) struct JsonPath(Vec<Segment>);
+
+#[derive(Debug, Clone)]
+enum Segment {
+    Index(usize),
+    // 读到key之前都是None
+    Key(Option<String>),
+}
+
+impl JsonPath {
+    pub fn enter_array(&mut self) {
+        self.0.push(Segment::Index(0));
+    }
+
+    pub fn enter_object(&mut self) {
+        self.0.push(Segment::Key(None));
+    }
+
+    pub fn leave(&mut self) {
+        self.0.pop();
+    }
+
+    /// 遇到逗号时，数组的下标加一，对象则等待下一个key
+    pub fn next(&mut self) {
+        match self.0.last_mut() {
+            Some(Segment::Index(i)) => *i += 1,
+            Some(Segment::Key(key)) => *key = None,
+            None => {}
+        }
+    }
+
+    pub fn set_key(&mut self, new_key: Option<String>) {
+        if let Some(Segment::Key(key)) = self.0.last_mut() {
+            *key = new_key;
+        }
+    }
+
+    /// 当前位置是否是对象中尚未读到的key
+    pub fn is_key(&self) -> bool {
+        matches!(self.0.last(), Some(Segment::Key(None)))
+    }
+
+    pub fn pointer(&self) -> String {
+        let mut res = String::new();
+        for seg in &self.0 {
+            res.push('/');
+            match seg {
+                Segment::Index(i) => res.push_str(&i.to_string()),
+                Segment::Key(key) => {
+                    let key = key.as_deref().unwrap_or_default();
+                    res.push_str(&key.replace('~', "~0").replace('/', "~1"));
+                }
+            }
+        }
+        res
+    }
+}
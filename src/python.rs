@@ -0,0 +1,209 @@
+use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyInt, PyList};
+use serde::de::IgnoredAny;
+
+use crate::value::{build, BuildValue};
+use crate::value_parser::unescape_string;
+use crate::{Allow, Error, Parser};
+
+// 与 python 版本 `partial_json_parser` 的异常保持一致：
+// 输入本身没有问题只是无法补全时为`PartialJSON`，否则为`MalformedJSON`
+create_exception!(partial_json_parser_rs, PartialJSON, PyValueError);
+create_exception!(partial_json_parser_rs, MalformedJSON, PyValueError);
+
+// python 版本中`NINFINITY`名为`_INFINITY`
+const ALLOW_NAMES: [(&str, Allow); 14] = [
+    ("STR", Allow::STR),
+    ("NUM", Allow::NUM),
+    ("ARR", Allow::ARR),
+    ("OBJ", Allow::OBJ),
+    ("NULL", Allow::NULL),
+    ("BOOL", Allow::BOOL),
+    ("NAN", Allow::NAN),
+    ("INFINITY", Allow::INFINITY),
+    ("_INFINITY", Allow::NINFINITY),
+    ("INF", Allow::INF),
+    ("SPECIAL", Allow::SPECIAL),
+    ("ATOM", Allow::ATOM),
+    ("COLLECTION", Allow::COLLECTION),
+    ("ALL", Allow::ALL),
+];
+
+fn to_py_err(err: Error) -> PyErr {
+    match err {
+        Error::Empty | Error::Uncompletable { .. } => PartialJSON::new_err(err.to_string()),
+        Error::MismatchedBracket { .. }
+        | Error::InvalidEscape { .. }
+        | Error::NotFound
+        | Error::NonFinite { .. } => MalformedJSON::new_err(err.to_string()),
+    }
+}
+
+/// 补全残缺的json字符串，`allow_partial`可以是`Allow`或者整数
+#[pyfunction]
+#[pyo3(signature = (json_string, allow_partial = Allow::ALL.bits()))]
+fn ensure_json(json_string: &str, allow_partial: u16) -> PyResult<String> {
+    Parser::parser_with(json_string, Allow::from_bits_truncate(allow_partial)).map_err(to_py_err)
+}
+
+/// 不持有GIL时构造的解析结果，之后再转换为python对象
+enum Node {
+    Null,
+    Bool(bool),
+    Int(i64),
+    // 超出`i64`范围的整数交给python的`int`处理
+    BigInt(String),
+    // 带小数点或者指数的数字保留原文，转换时才决定交给`float`还是`parse_float`
+    Real(String),
+    Float(f64),
+    Str(String),
+    Array(Vec<Node>),
+    Object(Vec<(String, Node)>),
+}
+
+impl BuildValue for Node {
+    type Object = Vec<(String, Node)>;
+
+    fn atom(s: &str) -> Option<Node> {
+        match s {
+            "null" => Some(Node::Null),
+            "true" => Some(Node::Bool(true)),
+            "false" => Some(Node::Bool(false)),
+            // 与`json.loads`一致，特殊的浮点数会保留下来
+            "NaN" => Some(Node::Float(f64::NAN)),
+            "Infinity" => Some(Node::Float(f64::INFINITY)),
+            "-Infinity" => Some(Node::Float(f64::NEG_INFINITY)),
+            _ if s.starts_with('"') => unescape_string(s).map(Node::Str),
+            // 先由`serde_json`检查数字的格式，避免接受`01`、`inf`之类的输入
+            _ if serde_json::from_str::<IgnoredAny>(s).is_err() => None,
+            _ if s.contains(['.', 'e', 'E']) => Some(Node::Real(s.to_string())),
+            _ => Some(
+                s.parse()
+                    .map_or_else(|_| Node::BigInt(s.to_string()), Node::Int),
+            ),
+        }
+    }
+
+    fn array(arr: Vec<Node>) -> Node {
+        Node::Array(arr)
+    }
+
+    fn object(obj: Vec<(String, Node)>) -> Node {
+        Node::Object(obj)
+    }
+
+    fn insert(obj: &mut Vec<(String, Node)>, key: String, value: Node) {
+        obj.push((key, value));
+    }
+}
+
+/// 与`json.loads`相同，数字的原文交给这两个函数构造，例如`parse_float=decimal.Decimal`
+struct NumberHooks {
+    parse_float: Option<PyObject>,
+    parse_int: Option<PyObject>,
+}
+
+impl Node {
+    fn into_py(self, py: Python<'_>, hooks: &NumberHooks) -> PyResult<PyObject> {
+        Ok(match self {
+            Node::Null => py.None(),
+            Node::Bool(b) => b.into_pyobject(py)?.to_owned().into_any().unbind(),
+            Node::Int(i) => match &hooks.parse_int {
+                Some(parse_int) => parse_int.call1(py, (i.to_string(),))?,
+                None => i.into_pyobject(py)?.into_any().unbind(),
+            },
+            Node::BigInt(s) => match &hooks.parse_int {
+                Some(parse_int) => parse_int.call1(py, (s,))?,
+                None => py.get_type::<PyInt>().call1((s,))?.unbind(),
+            },
+            Node::Real(s) => match &hooks.parse_float {
+                Some(parse_float) => parse_float.call1(py, (s,))?,
+                // 已经由`serde_json`检查过格式，超出范围时与python一样得到`inf`
+                None => s
+                    .parse::<f64>()
+                    .unwrap()
+                    .into_pyobject(py)?
+                    .into_any()
+                    .unbind(),
+            },
+            Node::Float(f) => f.into_pyobject(py)?.into_any().unbind(),
+            Node::Str(s) => s.into_pyobject(py)?.into_any().unbind(),
+            Node::Array(arr) => {
+                let list = PyList::empty(py);
+                for item in arr {
+                    list.append(item.into_py(py, hooks)?)?;
+                }
+                list.into_any().unbind()
+            }
+            // 重复的key与`json.loads`一样以最后一个为准
+            Node::Object(obj) => {
+                let dict = PyDict::new(py);
+                for (key, value) in obj {
+                    dict.set_item(key, value.into_py(py, hooks)?)?;
+                }
+                dict.into_any().unbind()
+            }
+        })
+    }
+}
+
+/// 补全并直接构造python对象，扫描期间会释放GIL，`json_string`可以是`str`或者`bytes`。
+/// 传入`parser`时则与 python 版本相同，补全之后交给`parser`解析。
+/// 整数总是保留完整的精度，需要精确的小数时可以传入`parse_float=decimal.Decimal`。
+#[pyfunction]
+#[pyo3(signature = (json_string, allow_partial = Allow::ALL.bits(), parser = None, *, parse_float = None, parse_int = None))]
+fn loads(
+    py: Python<'_>,
+    json_string: &Bound<'_, PyAny>,
+    allow_partial: u16,
+    parser: Option<PyObject>,
+    parse_float: Option<PyObject>,
+    parse_int: Option<PyObject>,
+) -> PyResult<PyObject> {
+    let json_string = match json_string.downcast::<PyBytes>() {
+        Ok(bytes) => std::str::from_utf8(bytes.as_bytes())
+            .map_err(|err| PyValueError::new_err(err.to_string()))?,
+        Err(_) => json_string.extract::<&str>()?,
+    };
+    let allow = Allow::from_bits_truncate(allow_partial);
+    if let Some(parser) = parser {
+        let completed = Parser::parser_with(json_string, allow).map_err(to_py_err)?;
+        return parser.call1(py, (completed,));
+    }
+    let node = py
+        .allow_threads(|| build::<Node>(json_string, allow.into()))
+        .map_err(to_py_err)?;
+    let hooks = NumberHooks {
+        parse_float,
+        parse_int,
+    };
+    node.into_py(py, &hooks)
+}
+
+#[pymodule]
+fn partial_json_parser_rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    // 用`enum.IntFlag`构造`Allow`，使得`Allow.STR | Allow.NUM`与整数都可以作为参数
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("module", "partial_json_parser_rs")?;
+    let members: Vec<(&str, u16)> = ALLOW_NAMES
+        .iter()
+        .map(|(name, flag)| (*name, flag.bits()))
+        .collect();
+    let allow = py
+        .import("enum")?
+        .getattr("IntFlag")?
+        .call(("Allow", members), Some(&kwargs))?;
+    for (name, _) in ALLOW_NAMES {
+        m.add(name, allow.getattr(name)?)?;
+    }
+    m.add("Allow", allow)?;
+
+    m.add("PartialJSON", py.get_type::<PartialJSON>())?;
+    m.add("MalformedJSON", py.get_type::<MalformedJSON>())?;
+    m.add_function(wrap_pyfunction!(ensure_json, m)?)?;
+    m.add_function(wrap_pyfunction!(loads, m)?)?;
+    Ok(())
+}
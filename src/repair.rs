@@ -0,0 +1,709 @@
+use std::fmt;
+
+use serde::de::IgnoredAny;
+
+use crate::dialect::is_json_atom;
+use crate::parser::{NonFinitePolicy, ParseSettings, Parser};
+use crate::value_parser::is_space;
+use crate::Error;
+
+/// 修复模式对输入做出的一处修改，offset都是输入中的字节序
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fix {
+    /// 在`offset`处的值之前补上逗号
+    InsertComma { offset: usize },
+    /// 在`offset`处的值之前补上冒号
+    InsertColon { offset: usize },
+    /// 对象中的key在`offset`处缺少值，补上`null`
+    InsertNull { offset: usize },
+    /// `offset`处的右括号与栈顶不匹配，换成闭合栈顶的`bracket`
+    CloseBracket { offset: usize, bracket: char },
+    /// 丢弃`offset`处多余的右括号
+    DropBracket { offset: usize, bracket: char },
+    /// 丢弃`offset`处多余的逗号
+    DropComma { offset: usize },
+    /// 丢弃`offset`处多余的冒号
+    DropColon { offset: usize },
+    /// 转义字符串中`offset`处的控制字符
+    EscapeControl { offset: usize },
+    /// 转义字符串中`offset`处的引号
+    EscapeQuote { offset: usize },
+    /// 把`offset`处单引号的字符串换成双引号
+    DoubleQuote { offset: usize },
+    /// 转义字符串中`offset`处不合法转义的反斜杠
+    EscapeBackslash { offset: usize },
+    /// 给`offset`处不带引号的key加上引号
+    QuoteKey { offset: usize },
+    /// 给`offset`处不是json字面量的值加上引号，作为字符串
+    QuoteString { offset: usize },
+}
+
+impl Fix {
+    /// 修改在输入中的位置
+    pub fn offset(&self) -> usize {
+        match self {
+            Self::InsertComma { offset }
+            | Self::InsertColon { offset }
+            | Self::InsertNull { offset }
+            | Self::CloseBracket { offset, .. }
+            | Self::DropBracket { offset, .. }
+            | Self::DropComma { offset }
+            | Self::DropColon { offset }
+            | Self::EscapeControl { offset }
+            | Self::EscapeQuote { offset }
+            | Self::DoubleQuote { offset }
+            | Self::EscapeBackslash { offset }
+            | Self::QuoteKey { offset }
+            | Self::QuoteString { offset } => *offset,
+        }
+    }
+}
+
+impl fmt::Display for Fix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InsertComma { offset } => write!(f, "inserted comma at {}", offset),
+            Self::InsertColon { offset } => write!(f, "inserted colon at {}", offset),
+            Self::InsertNull { offset } => write!(f, "inserted null at {}", offset),
+            Self::CloseBracket { offset, bracket } => {
+                write!(f, "inserted '{}' at {}", bracket, offset)
+            }
+            Self::DropBracket { offset, bracket } => {
+                write!(f, "dropped '{}' at {}", bracket, offset)
+            }
+            Self::DropComma { offset } => write!(f, "dropped comma at {}", offset),
+            Self::DropColon { offset } => write!(f, "dropped colon at {}", offset),
+            Self::EscapeControl { offset } => write!(f, "escaped control character at {}", offset),
+            Self::EscapeQuote { offset } => write!(f, "escaped quote at {}", offset),
+            Self::DoubleQuote { offset } => write!(f, "double-quoted string at {}", offset),
+            Self::EscapeBackslash { offset } => write!(f, "escaped backslash at {}", offset),
+            Self::QuoteKey { offset } => write!(f, "quoted key at {}", offset),
+            Self::QuoteString { offset } => write!(f, "quoted string at {}", offset),
+        }
+    }
+}
+
+/// 修复并补全之后的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repaired {
+    pub completed: String,
+    /// 按照在输入中的位置排列，不包括补全时被丢弃的内容中的修复
+    pub fixes: Vec<Fix>,
+}
+
+/// 修复不合法的json之后再补全：补上缺失的逗号、冒号以及对象中缺失的值，
+/// 把不匹配的右括号换成闭合栈顶的括号，丢弃多余的右括号、逗号和冒号，
+/// 转义字符串中的控制字符、引号和不合法的转义，把单引号的字符串换成双引号，
+/// 给不带引号的key以及字符串加上引号。
+///
+/// 作为值的字符串中，引号之后（跳过空白）是`,:}]"`、同样的引号或者输入的结尾时，才视为字符串的结束。
+/// 对象中key的位置出现了数字、数组等无法作为key的值，或者修复之后仍然不是合法的json时返回错误，
+/// 后者的offset是修复之后的结果无法解析的位置所对应的输入中的位置。
+pub fn repair(in_str: &str, settings: impl Into<ParseSettings>) -> Result<Repaired, Error> {
+    let mut repairer = Repairer {
+        src: in_str,
+        out: String::with_capacity(in_str.len()),
+        fixes: Vec::new(),
+        stack: Vec::new(),
+        root: Expect::Value,
+        last_value_end: 0,
+        last_comma: None,
+        edits: Vec::new(),
+        boundaries: Vec::new(),
+    };
+    repairer.run()?;
+    let out = repairer.apply_edits();
+    let completed = Parser::parser_with(&out, settings)?;
+    // 无法修复的输入返回错误，而不是不合法的结果
+    let strict = NonFinitePolicy::Null.apply_text(&completed)?;
+    // 补全的部分来自输入中尚未结束的内容
+    let kept = kept_len(&out, &completed);
+    if let Err(err) = serde_json::from_str::<IgnoredAny>(&strict) {
+        let offset = unstrict_offset(&completed, text_offset(&strict, err.line(), err.column()));
+        return Err(Error::Uncompletable {
+            offset: repairer.source_offset(offset.min(kept)),
+        });
+    }
+    // 补全时丢弃的内容中的修复没有出现在结果里
+    let mut fixes = std::mem::take(&mut repairer.fixes);
+    if kept < out.len() {
+        let cut = repairer.source_offset(kept);
+        fixes.retain(|fix| fix.offset() < cut);
+    }
+    Ok(Repaired { completed, fixes })
+}
+
+/// 补全之后的结果中保留了`out`的多少字节，补全时调整的空白不算作丢弃
+fn kept_len(out: &str, completed: &str) -> usize {
+    let mut completed = completed.chars().filter(|c| !is_space(*c)).peekable();
+    for (idx, c) in out.char_indices() {
+        if is_space(c) {
+            continue;
+        }
+        if completed.next_if_eq(&c).is_none() {
+            return idx;
+        }
+    }
+    out.len()
+}
+
+/// serde_json错误中的行和列在`text`中的位置，列从1开始，按照字节计数
+fn text_offset(text: &str, line: usize, column: usize) -> usize {
+    let line_start = text
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum::<usize>();
+    (line_start + column.saturating_sub(1)).min(text.len())
+}
+
+/// 替换`NaN`等之前的`text`中，对应替换之后的位置`offset`的位置
+fn unstrict_offset(text: &str, offset: usize) -> usize {
+    let strict_pos = |mut pos: usize| {
+        // 只在出错时调用，`text`总是严格的json
+        let _ = NonFinitePolicy::Null.apply_text_at(text, &mut pos);
+        pos
+    };
+    let mut bounds = 0..text.len();
+    while bounds.start < bounds.end {
+        let mid = (bounds.start + bounds.end).div_ceil(2);
+        if strict_pos(mid) <= offset {
+            bounds.start = mid;
+        } else {
+            bounds.end = mid - 1;
+        }
+    }
+    bounds.start
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Expect {
+    Value,
+    Key,
+    Colon,
+    CommaOrEnd,
+}
+
+struct Repairer<'a> {
+    src: &'a str,
+    out: String,
+    fixes: Vec<Fix>,
+    // 每一层的右括号，以及这一层接下来需要的内容
+    stack: Vec<(char, Expect)>,
+    root: Expect,
+    // 上一个值在输出中的结束位置，补上的逗号和冒号紧跟在它之后
+    last_value_end: usize,
+    // 上一个逗号在输出以及输入中的位置，之后还没有出现值
+    last_comma: Option<(usize, usize)>,
+    // 补上的逗号、冒号以及丢弃的逗号，按照在输出中的位置排列，最后一次性修改，
+    // 否则每处修复都需要移动之后的全部输出
+    edits: Vec<(usize, Edit)>,
+    // 每个token在输入以及输出中的起始位置
+    boundaries: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Edit {
+    Insert(char),
+    Remove,
+}
+
+impl Repairer<'_> {
+    fn expect(&mut self) -> &mut Expect {
+        match self.stack.last_mut() {
+            Some((_, expect)) => expect,
+            None => &mut self.root,
+        }
+    }
+
+    /// 逗号之后需要的内容
+    fn next_expect(&self) -> Expect {
+        match self.stack.last() {
+            Some(('}', _)) => Expect::Key,
+            _ => Expect::Value,
+        }
+    }
+
+    fn run(&mut self) -> Result<(), Error> {
+        let mut idx = 0;
+        while let Some(c) = self.src[idx..].chars().next() {
+            if !is_space(c) {
+                self.boundaries.push((idx, self.out.len()));
+            }
+            idx = match c {
+                _ if is_space(c) => {
+                    self.out.push(c);
+                    idx + c.len_utf8()
+                }
+                '"' | '\'' => {
+                    let is_key = self.before_value(idx);
+                    if c == '\'' {
+                        self.fixes.push(Fix::DoubleQuote { offset: idx });
+                    }
+                    let end = self.string(idx, c, is_key);
+                    self.after_value(is_key);
+                    end
+                }
+                '{' | '[' => {
+                    if self.before_value(idx) {
+                        return Err(Error::Uncompletable { offset: idx });
+                    }
+                    self.out.push(c);
+                    let (closer, expect) = if c == '{' {
+                        ('}', Expect::Key)
+                    } else {
+                        (']', Expect::Value)
+                    };
+                    self.stack.push((closer, expect));
+                    idx + 1
+                }
+                '}' | ']' => {
+                    self.close(idx, c);
+                    idx + 1
+                }
+                ',' => {
+                    self.fill_missing_value(idx);
+                    if *self.expect() == Expect::CommaOrEnd {
+                        *self.expect() = self.next_expect();
+                        self.last_comma = Some((self.out.len(), idx));
+                        self.out.push(',');
+                    } else {
+                        // `[,1]`以及`[1,,2]`
+                        self.fixes.push(Fix::DropComma { offset: idx });
+                    }
+                    idx + 1
+                }
+                ':' => {
+                    let expect = self.expect();
+                    if *expect == Expect::Colon {
+                        *expect = Expect::Value;
+                        self.out.push(':');
+                    } else {
+                        // `[1: 2]`以及`{"a":: 1}`
+                        self.fixes.push(Fix::DropColon { offset: idx });
+                    }
+                    idx + 1
+                }
+                _ => {
+                    let len = self.src[idx..]
+                        .find(|c| {
+                            is_space(c) || matches!(c, ',' | ':' | '[' | ']' | '{' | '}' | '"')
+                        })
+                        .unwrap_or(self.src.len() - idx);
+                    let atom = &self.src[idx..idx + len];
+                    let at_end = idx + len == self.src.len();
+                    let is_key = self.before_value(idx);
+                    if is_key {
+                        // 后面跟着冒号或者形如标识符的内容才是key，`{"a": 1 2}`中的`2`无法修复
+                        let colon = self.src[idx + len..].trim_start_matches(is_space);
+                        if !colon.starts_with(':') && !is_identifier(atom) {
+                            return Err(Error::Uncompletable { offset: idx });
+                        }
+                    }
+                    if at_end && (is_key || is_json_atom(atom, false)) {
+                        // 输入在key或者字面量的中间结束，交给补全处理
+                        self.out.push_str(atom);
+                    } else if is_key || !is_json_atom(atom, true) {
+                        let fix = if is_key {
+                            Fix::QuoteKey { offset: idx }
+                        } else {
+                            Fix::QuoteString { offset: idx }
+                        };
+                        self.fixes.push(fix);
+                        self.out.push('"');
+                        self.out.push_str(&atom.replace('\\', "\\\\"));
+                        if !at_end {
+                            self.out.push('"');
+                        }
+                    } else {
+                        self.out.push_str(atom);
+                    }
+                    self.after_value(is_key);
+                    idx + len
+                }
+            };
+        }
+        Ok(())
+    }
+
+    /// 在值或者key开始之前补上缺失的逗号和冒号，返回这是否是一个key
+    fn before_value(&mut self, idx: usize) -> bool {
+        self.last_comma = None;
+        let expect = *self.expect();
+        match expect {
+            Expect::CommaOrEnd if !self.stack.is_empty() => {
+                self.fixes.push(Fix::InsertComma { offset: idx });
+                self.edits.push((self.last_value_end, Edit::Insert(',')));
+                *self.expect() = self.next_expect();
+                *self.expect() == Expect::Key
+            }
+            Expect::Colon => {
+                self.insert_colon(idx);
+                false
+            }
+            _ => expect == Expect::Key,
+        }
+    }
+
+    fn insert_colon(&mut self, idx: usize) {
+        self.fixes.push(Fix::InsertColon { offset: idx });
+        self.edits.push((self.last_value_end, Edit::Insert(':')));
+        *self.expect() = Expect::Value;
+    }
+
+    /// 对象中的key之后直接出现了逗号或者右括号时补上值，例如`{"a": }`以及`{"a", "b": 1}`
+    fn fill_missing_value(&mut self, idx: usize) {
+        if !matches!(self.stack.last(), Some(('}', _))) {
+            return;
+        }
+        match *self.expect() {
+            Expect::Colon => self.insert_colon(idx),
+            Expect::Value => {}
+            _ => return,
+        }
+        self.fixes.push(Fix::InsertNull { offset: idx });
+        self.out.push_str("null");
+        self.after_value(false);
+    }
+
+    fn after_value(&mut self, is_key: bool) {
+        self.last_value_end = self.out.len();
+        *self.expect() = if is_key {
+            Expect::Colon
+        } else {
+            Expect::CommaOrEnd
+        };
+    }
+
+    fn close(&mut self, idx: usize, c: char) {
+        let Some(&(closer, _)) = self.stack.last() else {
+            self.fixes.push(Fix::DropBracket {
+                offset: idx,
+                bracket: c,
+            });
+            return;
+        };
+        if let Some((out_idx, src_idx)) = self.last_comma.take() {
+            self.edits.push((out_idx, Edit::Remove));
+            self.fixes.push(Fix::DropComma { offset: src_idx });
+        }
+        self.fill_missing_value(idx);
+        // 不匹配的右括号只闭合栈顶，而不是去匹配外层的括号
+        if closer != c {
+            self.fixes.push(Fix::CloseBracket {
+                offset: idx,
+                bracket: closer,
+            });
+        }
+        self.stack.pop();
+        self.out.push(closer);
+        self.after_value(false);
+    }
+
+    /// 把补上以及丢弃的分隔符应用到输出中，同时调整每个token在输出中的位置
+    fn apply_edits(&mut self) -> String {
+        self.edits.sort_by_key(|(pos, _)| *pos);
+        let mut res = String::with_capacity(self.out.len() + self.edits.len());
+        let mut last = 0;
+        let mut boundaries = self.boundaries.iter_mut().peekable();
+        for &(pos, edit) in &self.edits {
+            // 插入的内容位于同一位置的token之前
+            while let Some((_, out_idx)) = boundaries.next_if(|(_, out_idx)| *out_idx < pos) {
+                *out_idx = res.len() + out_idx.saturating_sub(last);
+            }
+            res.push_str(&self.out[last..pos]);
+            last = match edit {
+                Edit::Insert(c) => {
+                    res.push(c);
+                    pos
+                }
+                Edit::Remove => pos + 1,
+            };
+        }
+        for (_, out_idx) in boundaries {
+            *out_idx = res.len() + out_idx.saturating_sub(last);
+        }
+        res.push_str(&self.out[last..]);
+        res
+    }
+
+    /// 输出中的位置所在的token在输入中的位置
+    fn source_offset(&self, offset: usize) -> usize {
+        let pos = self
+            .boundaries
+            .partition_point(|(_, out_idx)| *out_idx <= offset);
+        let (src_idx, out_idx) = pos
+            .checked_sub(1)
+            .map_or((0, 0), |pos| self.boundaries[pos]);
+        let limit = self
+            .boundaries
+            .get(pos)
+            .map_or(self.src.len(), |(src_idx, _)| *src_idx);
+        (src_idx + offset - out_idx).min(limit)
+    }
+
+    /// 修复从`start`开始、以`quote`开头的字符串，返回字符串之后的位置，单引号的字符串换成双引号。
+    /// key中的引号总是视为key的结束，因为之后可能缺少冒号
+    fn string(&mut self, start: usize, quote: char, is_key: bool) -> usize {
+        self.out.push('"');
+        let mut idx = start + 1;
+        while let Some(c) = self.src[idx..].chars().next() {
+            match c {
+                '\\' => {
+                    let next = self.src[idx + 1..].chars().next();
+                    // `\u`之后需要四位十六进制数字，输入在其中结束时交给补全处理
+                    let hex = match next {
+                        Some('u') => self.src[idx + 2..]
+                            .chars()
+                            .take(4)
+                            .take_while(char::is_ascii_hexdigit)
+                            .count(),
+                        _ => 0,
+                    };
+                    let unicode = hex == 4 || idx + 2 + hex == self.src.len();
+                    match next {
+                        Some('u') if unicode => {
+                            self.out.push_str(&self.src[idx..idx + 2 + hex]);
+                            idx += 2 + hex;
+                            continue;
+                        }
+                        Some('\'') if quote == '\'' => {
+                            self.out.push('\'');
+                            idx += 2;
+                            continue;
+                        }
+                        Some('"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't') | None => {
+                            self.out.push('\\');
+                            if let Some(next) = next {
+                                self.out.push(next);
+                            }
+                            idx += 1 + next.map_or(0, char::len_utf8);
+                            continue;
+                        }
+                        Some(_) => {
+                            self.fixes.push(Fix::EscapeBackslash { offset: idx });
+                            self.out.push_str("\\\\");
+                        }
+                    }
+                }
+                _ if c == quote => {
+                    let next = self.src[idx + 1..]
+                        .trim_start_matches(is_space)
+                        .chars()
+                        .next();
+                    let ends = |c: char| matches!(c, ',' | ':' | '}' | ']' | '"') || c == quote;
+                    if is_key || next.is_none_or(ends) {
+                        self.out.push('"');
+                        return idx + 1;
+                    }
+                    self.fixes.push(Fix::EscapeQuote { offset: idx });
+                    self.out.push_str(if quote == '"' { "\\\"" } else { "'" });
+                }
+                // 单引号的字符串中的双引号需要转义
+                '"' => self.out.push_str("\\\""),
+                _ if (c as u32) < 0x20 => {
+                    self.fixes.push(Fix::EscapeControl { offset: idx });
+                    match c {
+                        '\n' => self.out.push_str("\\n"),
+                        '\r' => self.out.push_str("\\r"),
+                        '\t' => self.out.push_str("\\t"),
+                        _ => self.out.push_str(&format!("\\u{:04x}", c as u32)),
+                    }
+                }
+                _ => self.out.push(c),
+            }
+            idx += c.len_utf8();
+        }
+        idx
+    }
+}
+
+/// 可以不带引号作为key的内容
+fn is_identifier(atom: &str) -> bool {
+    atom.chars()
+        .all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+        && atom.starts_with(|c: char| c.is_alphabetic() || c == '_' || c == '$')
+}
+
+#[cfg(test)]
+mod test {
+    use super::{repair, Fix};
+    use crate::test_utils::arb_json;
+    use crate::{Allow, Error};
+    use proptest::prelude::*;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn test_repair() {
+        let res = repair(r#"{"a": 1 "b" 2, c: [1 2}]"#, Allow::ALL).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&res.completed).unwrap(),
+            json!({"a": 1, "b": 2, "c": [1, 2]})
+        );
+        assert_eq!(
+            res.fixes,
+            [
+                Fix::InsertComma { offset: 8 },
+                Fix::InsertColon { offset: 12 },
+                Fix::QuoteKey { offset: 15 },
+                Fix::InsertComma { offset: 21 },
+                Fix::CloseBracket {
+                    offset: 22,
+                    bracket: ']'
+                },
+                Fix::CloseBracket {
+                    offset: 23,
+                    bracket: '}'
+                },
+            ]
+        );
+
+        let res = repair("[\"say \"hi\" now\n\", \"a\\qb\", 1,]", Allow::ALL).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&res.completed).unwrap(),
+            json!(["say \"hi\" now\n", "a\\qb", 1])
+        );
+        assert_eq!(
+            res.fixes,
+            [
+                Fix::EscapeQuote { offset: 6 },
+                Fix::EscapeQuote { offset: 9 },
+                Fix::EscapeControl { offset: 14 },
+                Fix::EscapeBackslash { offset: 20 },
+                Fix::DropComma { offset: 27 },
+            ]
+        );
+
+        // 截断的输入仍然会被补全
+        let res = repair(r#"{"a": [1 2"#, Allow::ALL).unwrap();
+        assert_eq!(res.completed, r#"{"a": [1,2]}"#);
+        assert_eq!(res.fixes, [Fix::InsertComma { offset: 9 }]);
+
+        // 补全时丢弃的内容中的修复不会被报告
+        let res = repair(r#"{"a": "he said "hi""}"#, Allow::ALL).unwrap();
+        assert_eq!(res.completed, r#"{"a": "he said \"hi"}"#);
+        assert_eq!(res.fixes, [Fix::EscapeQuote { offset: 15 }]);
+    }
+
+    #[test]
+    fn test_repair_structure() {
+        let cases = [
+            // 多余的冒号
+            (
+                "[1: 2]",
+                "[1, 2]",
+                vec![Fix::DropColon { offset: 2 }, Fix::InsertComma { offset: 4 }],
+            ),
+            // 不带引号的key以及不是字面量的值
+            (
+                "{a b}",
+                r#"{"a": "b"}"#,
+                vec![
+                    Fix::QuoteKey { offset: 1 },
+                    Fix::InsertColon { offset: 3 },
+                    Fix::QuoteString { offset: 3 },
+                ],
+            ),
+            // 缺少的值
+            (
+                r#"{"a": }"#,
+                r#"{"a": null}"#,
+                vec![Fix::InsertNull { offset: 6 }],
+            ),
+            (
+                r#"{"a", "b": 1}"#,
+                r#"{"a":null, "b": 1}"#,
+                vec![
+                    Fix::InsertColon { offset: 4 },
+                    Fix::InsertNull { offset: 4 },
+                ],
+            ),
+            // 不匹配的右括号只闭合栈顶
+            (
+                r#"{"a": [1, 2}, "b": 3}"#,
+                r#"{"a": [1, 2], "b": 3}"#,
+                vec![Fix::CloseBracket {
+                    offset: 11,
+                    bracket: ']',
+                }],
+            ),
+            // 单引号的字符串换成双引号，而不是作为字面量加上引号
+            (
+                "{'a': 'it\\'s \"x\"'}",
+                r#"{"a": "it's \"x\""}"#,
+                vec![
+                    Fix::DoubleQuote { offset: 1 },
+                    Fix::DoubleQuote { offset: 6 },
+                ],
+            ),
+            (
+                "['a'b', 'c",
+                r#"["a'b","c"]"#,
+                vec![
+                    Fix::DoubleQuote { offset: 1 },
+                    Fix::EscapeQuote { offset: 3 },
+                    Fix::DoubleQuote { offset: 8 },
+                ],
+            ),
+            // 不合法的`\u`转义
+            (
+                r#""\u12G4""#,
+                r#""\\u12G4""#,
+                vec![Fix::EscapeBackslash { offset: 1 }],
+            ),
+        ];
+        for (input, completed, fixes) in cases {
+            let res = repair(input, Allow::ALL).unwrap();
+            assert_eq!(res.completed, completed, "input: {:?}", input);
+            assert_eq!(res.fixes, fixes, "input: {:?}", input);
+        }
+
+        // key的位置出现了无法作为key的值
+        assert_eq!(
+            repair(r#"{"a": 1 2}"#, Allow::ALL),
+            Err(Error::Uncompletable { offset: 8 })
+        );
+        assert_eq!(
+            repair(r#"{[1]: 2}"#, Allow::ALL),
+            Err(Error::Uncompletable { offset: 1 })
+        );
+        // 修复之后仍然无法解析时，返回无法解析的位置在输入中的位置
+        assert_eq!(
+            repair("[1 2] [3]", Allow::ALL),
+            Err(Error::Uncompletable { offset: 6 })
+        );
+        assert_eq!(
+            repair("{a: 1} x", Allow::ALL),
+            Err(Error::Uncompletable { offset: 7 })
+        );
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(256))]
+        #[test]
+        fn repair_valid_unchanged(s in arb_json()) {
+            // 合法的输入只会转义其中的控制字符
+            let s = s.to_string();
+            let res = repair(&s, Allow::ALL).unwrap();
+            prop_assert!(
+                res.fixes.iter().all(|fix| matches!(fix, Fix::EscapeControl { .. })),
+                "fixes: {:?}", res.fixes
+            );
+            prop_assert_eq!(
+                json5::from_str::<Value>(&res.completed).unwrap(),
+                json5::from_str::<Value>(&s).unwrap()
+            );
+        }
+
+        #[test]
+        fn repair_result_is_json(s in r#"[\[\]{}"',:\\ua-z0-9G\n ]{0,24}"#) {
+            // 要么返回错误，要么返回合法的json
+            if let Ok(res) = repair(&s, Allow::ALL) {
+                prop_assert!(
+                    json5::from_str::<Value>(&res.completed).is_ok(),
+                    "completed: {:?}", res.completed
+                );
+            }
+        }
+    }
+}
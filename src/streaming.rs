@@ -0,0 +1,169 @@
+use crate::parser::{ParseSettings, Parser};
+use crate::{Completion, Error, Warning};
+
+/// 增量解析器，每次只扫描新到达的部分。
+///
+/// 括号栈、字符串状态以及最后一个分隔符的位置都会在多次`feed`之间保留，
+/// 因此扫描的总开销与收到的字节数成线性关系；`complete`的结果与对当前
+/// 全部输入调用[`Parser::parser_with`]一致。
+#[derive(Debug)]
+pub struct StreamingParser {
+    parser: Parser<'static>,
+}
+
+impl Default for StreamingParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StreamingParser {
+    pub fn new() -> StreamingParser {
+        Self::with_settings(ParseSettings::default())
+    }
+
+    pub fn with_settings(settings: impl Into<ParseSettings>) -> StreamingParser {
+        let mut parser = Parser::parser_owned(settings.into());
+        parser.parse();
+        StreamingParser { parser }
+    }
+
+    pub fn feed(&mut self, chunk: &str) {
+        self.parser.push_str(chunk);
+    }
+
+    /// 对目前收到的全部输入进行补全
+    pub fn complete(&self) -> Result<String, Error> {
+        self.parser.complete()
+    }
+
+    /// 与`complete`相同，但是返回[`Completion`]，可以只把`suffix`接到自己的缓冲区之后
+    pub fn completion(&self) -> Result<Completion, Error> {
+        self.parser.completion()
+    }
+
+    /// 目前为止扫描过程中收集到的警告
+    pub fn warnings(&self) -> &[Warning] {
+        self.parser.warnings()
+    }
+
+    /// 目前收到的全部输入
+    pub fn as_str(&self) -> &str {
+        self.parser.input_str()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::StreamingParser;
+    use crate::test_utils::arb_json;
+    use crate::{Allow, Dialect, EscapeMode, ParseSettings, Parser};
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_feed() {
+        let mut parser = StreamingParser::with_settings(Allow::ALL);
+        assert!(parser.complete().is_err());
+        parser.feed(r#"{"a": [1, "#);
+        assert_eq!(parser.complete().unwrap(), r#"{"a": [1]}"#);
+        parser.feed(r#""x\"#);
+        assert_eq!(parser.complete().unwrap(), r#"{"a": [1,"x"]}"#);
+        parser.feed(r#"ny"], "b": tr"#);
+        assert_eq!(
+            parser.complete().unwrap(),
+            r#"{"a": [1, "x\ny"], "b":true}"#
+        );
+        assert_eq!(parser.as_str(), r#"{"a": [1, "x\ny"], "b": tr"#);
+    }
+
+    #[test]
+    fn test_feed_json5() {
+        let settings = ParseSettings::new()
+            .allow(Allow::ALL)
+            .dialect(Dialect::Json5)
+            .keep_dialect(true);
+        let src = "{a: ['x\\'y', 0x1F, ], // c\n b: .5";
+        let mut parser = StreamingParser::with_settings(settings.clone());
+        for (idx, c) in src.char_indices() {
+            parser.feed(&src[idx..idx + c.len_utf8()]);
+            let expected = Parser::parser_with(&src[..idx + c.len_utf8()], settings.clone());
+            assert_eq!(parser.complete(), expected);
+            // 补全的详细信息同样保留方言，并且各个位置都相对于原始的输入
+            let res = parser.completion().unwrap();
+            assert_eq!(Ok(&res.text), expected.as_ref());
+            assert_eq!(
+                parser.as_str()[..res.kept_prefix_len].to_string() + &res.suffix,
+                res.text
+            );
+        }
+        assert_eq!(parser.as_str(), src);
+        assert_eq!(
+            parser.complete().unwrap(),
+            "{a: ['x\\'y', 0x1F, ], // c\n b:0.5}"
+        );
+
+        // 不保留方言时结果是严格的json
+        let src = "{a: 'x', b: [1, 2";
+        let mut parser = StreamingParser::with_settings(settings.keep_dialect(false));
+        parser.feed(src);
+        let res = parser.completion().unwrap();
+        assert_eq!(res.text, r#"{"a": "x", "b": [1,2]}"#);
+        assert_eq!((res.kept_prefix_len, res.dropped), (1, 1..src.len()));
+        assert_eq!(
+            src[..res.kept_prefix_len].to_string() + &res.suffix,
+            res.text
+        );
+        assert_eq!(res.incomplete_paths, ["", "/b", "/b/1"]);
+    }
+
+    #[test]
+    fn test_feed_lenient_escape() {
+        let settings = ParseSettings::new()
+            .allow(Allow::ALL)
+            .escape_mode(EscapeMode::Lenient);
+        let src = r#"{"a\q": ["b\u00e9\x", "\\"], "c": "d\w"#;
+        let mut parser = StreamingParser::with_settings(settings.clone());
+        for (idx, c) in src.char_indices() {
+            parser.feed(&src[idx..idx + c.len_utf8()]);
+            let expected = Parser::parser_with(&src[..idx + c.len_utf8()], settings.clone());
+            assert_eq!(parser.complete(), expected);
+        }
+        assert_eq!(parser.warnings().len(), 3);
+        assert_eq!(
+            parser.complete().unwrap(),
+            r#"{"a\\q": ["b\u00e9\\x", "\\"], "c":"d\\w"}"#
+        );
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(256))]
+        #[test]
+        fn streaming_same_as_parser(s in arb_json(), chunk_len in 1usize..8) {
+            let s = s.to_string();
+            let mut parser = StreamingParser::with_settings(Allow::ALL);
+            let chars: Vec<char> = s.chars().collect();
+            let mut fed = 0;
+            for chunk in chars.chunks(chunk_len) {
+                let chunk: String = chunk.iter().collect();
+                parser.feed(&chunk);
+                fed += chunk.len();
+                assert_eq!(parser.complete(), Parser::parser_with(&s[..fed], Allow::ALL));
+            }
+        }
+
+        #[test]
+        fn streaming_repaired_same_as_parser(s in r#"[\[\]{}"',:\\ux0-9a-fG ]{0,24}"#) {
+            let lenient = ParseSettings::new()
+                .allow(Allow::ALL)
+                .escape_mode(EscapeMode::Lenient);
+            for settings in [lenient.clone(), lenient.dialect(Dialect::Json5)] {
+                let mut parser = StreamingParser::with_settings(settings.clone());
+                for (idx, c) in s.char_indices() {
+                    parser.feed(&s[idx..idx + c.len_utf8()]);
+                    let expected = Parser::parser_with(&s[..idx + c.len_utf8()], settings.clone());
+                    prop_assert_eq!(parser.complete(), expected);
+                }
+            }
+        }
+    }
+}
@@ -115,6 +115,22 @@ impl Tester {
     }
 }
 
+/// 把浮点数统一为`f64`的表示，开启`arbitrary_precision`时`Value`中的数字是按照原文比较的
+pub fn normalize(value: serde_json::Value) -> serde_json::Value {
+    use serde_json::{Number, Value};
+    match value {
+        Value::Number(n) if n.is_f64() => n
+            .as_f64()
+            .and_then(Number::from_f64)
+            .map_or(Value::Null, Value::Number),
+        Value::Array(arr) => Value::Array(arr.into_iter().map(normalize).collect()),
+        Value::Object(obj) => {
+            Value::Object(obj.into_iter().map(|(k, v)| (k, normalize(v))).collect())
+        }
+        value => value,
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum Json {
     Null,
@@ -161,7 +177,11 @@ impl Serialize for Json {
 
 impl fmt::Display for Json {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let s = json5::to_string(self).unwrap();
+        // json5 在解析时不接受字符串中未转义的行分隔符，输出时需要转义
+        let s = json5::to_string(self)
+            .unwrap()
+            .replace('\u{2028}', "\\u2028")
+            .replace('\u{2029}', "\\u2029");
         write!(f, "{}", &s)
     }
 }
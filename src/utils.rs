@@ -44,23 +44,32 @@ pub fn add_title(s: &str) -> String {
     )
 }
 
+#[deprecated(note = "诊断信息改为通过`Parser::warnings`以及`log` feature输出")]
 #[macro_export]
 macro_rules! debug_println {
     ($($arg:tt)*) => {
-        if cfg!(debug_assertions) {
-            println!($($arg)*);
-        }
+        // 不再输出任何内容，只检查参数
+        let _ = format_args!($($arg)*);
     };
 }
 
+#[deprecated(note = "诊断信息改为通过`Parser::warnings`以及`log` feature输出")]
 #[macro_export]
 macro_rules! debug_print {
     ($($arg:tt)*) => {
-        if cfg!(debug_assertions) {
-            print!($($arg)*);
-        }
+        // 不再输出任何内容，只检查参数
+        let _ = format_args!($($arg)*);
+    };
+}
+
+// 开启`log` feature之后通过`log`输出诊断信息，否则不输出任何内容
+macro_rules! log_warn {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "log")]
+        log::warn!($($arg)*);
     };
 }
+pub(crate) use log_warn;
 
 pub fn complement_after<'a>(full: &'a str, part: &'a str) -> Option<&'a str> {
     full.find(part).map(|index| &full[index + part.len()..])
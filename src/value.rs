@@ -0,0 +1,348 @@
+use serde_json::{Map, Value};
+
+use crate::parser::{CharType, NonFinitePolicy, ParseSettings, Parser, ScanVisitor};
+use crate::utils::RunState;
+use crate::value_parser::{is_space, unescape_string};
+use crate::{Allow, Error};
+
+/// 直接由残缺的输入构造`serde_json::Value`，补全规则与[`Parser::parser_with`]相同，
+/// 但是只扫描一遍输入，不需要先生成字符串再交给`serde_json`解析。
+///
+/// `NaN`、`Infinity`以及`-Infinity`无法用`Value`表示，保留时会和`serde_json`一样变成`null`，
+/// 也可以通过[`ParseSettings::non_finite`]替换为字符串或者返回错误。
+/// 开启`preserve_order` feature之后，对象会保持key的插入顺序；
+/// 开启`arbitrary_precision` feature之后，数字会保留原文，超出`f64`精度的id、金额等不会被改变。
+pub fn loads(in_str: &str, settings: impl Into<ParseSettings>) -> Result<Value, Error> {
+    build(in_str, settings.into())
+}
+
+/// 可以由[`build`]一遍扫描构造出来的值
+pub(crate) trait BuildValue: Sized {
+    type Object: Default;
+
+    /// 解析一个完整的原子值，不合法时返回`None`
+    fn atom(s: &str) -> Option<Self>;
+    fn array(arr: Vec<Self>) -> Self;
+    fn object(obj: Self::Object) -> Self;
+    fn insert(obj: &mut Self::Object, key: String, value: Self);
+}
+
+impl BuildValue for Value {
+    type Object = Map<String, Value>;
+
+    // 特殊的浮点数没有对应的`Value`
+    fn atom(s: &str) -> Option<Value> {
+        match s {
+            "NaN" | "Infinity" | "-Infinity" => Some(Value::Null),
+            _ if s.starts_with('"') => unescape_string(s).map(Value::String),
+            _ => serde_json::from_str(s).ok(),
+        }
+    }
+
+    fn array(arr: Vec<Value>) -> Value {
+        Value::Array(arr)
+    }
+
+    fn object(obj: Map<String, Value>) -> Value {
+        Value::Object(obj)
+    }
+
+    fn insert(obj: &mut Map<String, Value>, key: String, value: Value) {
+        obj.insert(key, value);
+    }
+}
+
+pub(crate) fn build<T: BuildValue>(in_str: &str, settings: ParseSettings) -> Result<T, Error> {
+    if in_str.trim_matches(is_space).is_empty() {
+        return build_empty(&settings, Error::Empty);
+    }
+    let mut builder = ValueBuilder {
+        non_finite: settings.non_finite,
+        ..Default::default()
+    };
+    let mut parser = Parser::with_settings(in_str, settings);
+    parser.parse_with(&mut builder);
+    if let RunState::Error(err) = parser.is_parsed() {
+        return Err(parser.source_error(err.clone()));
+    }
+    if let Some((src, settings)) = parser.reparse_src() {
+        return build(&src, settings).map_err(|err| parser.reparsed_error(err));
+    }
+    builder
+        .finish(&parser)
+        .map_err(|err| parser.source_error(err))
+}
+
+/// 输入为空或者根节点的原子值被丢弃时，使用设置中的默认值
+fn build_empty<T: BuildValue>(settings: &ParseSettings, err: Error) -> Result<T, Error> {
+    let Some(value) = &settings.empty_value else {
+        return Err(err);
+    };
+    // 默认值总是严格的json，与输入的方言和补全设置无关
+    build(value, Allow::ALL.into())
+}
+
+enum Frame<T: BuildValue> {
+    Array(Vec<T>),
+    // 对象，以及已经读到冒号但是还没有值的key
+    Object(T::Object, Option<String>),
+}
+
+impl<T: BuildValue> Frame<T> {
+    fn char_type(&self) -> CharType {
+        match self {
+            Frame::Array(_) => CharType::LFB,
+            Frame::Object(..) => CharType::LCB,
+        }
+    }
+
+    fn push(&mut self, value: T) {
+        match self {
+            Frame::Array(arr) => arr.push(value),
+            Frame::Object(map, key) => {
+                if let Some(key) = key.take() {
+                    T::insert(map, key, value);
+                }
+            }
+        }
+    }
+
+    fn drop_key(&mut self) {
+        if let Frame::Object(_, key) = self {
+            key.take();
+        }
+    }
+
+    fn into_value(self) -> T {
+        match self {
+            Frame::Array(arr) => T::array(arr),
+            Frame::Object(map, _) => T::object(map),
+        }
+    }
+}
+
+struct ValueBuilder<T: BuildValue> {
+    frames: Vec<Frame<T>>,
+    root: Option<T>,
+    // 上一个结构字符之后的位置，两个结构字符之间的内容就是一个完整的原子值
+    last: usize,
+    error: Option<Error>,
+    non_finite: NonFinitePolicy,
+}
+
+impl<T: BuildValue> Default for ValueBuilder<T> {
+    fn default() -> Self {
+        ValueBuilder {
+            frames: Vec::new(),
+            root: None,
+            last: 0,
+            error: None,
+            non_finite: NonFinitePolicy::default(),
+        }
+    }
+}
+
+impl<T: BuildValue> ValueBuilder<T> {
+    fn atom(&self, atom: &str, offset: usize) -> Result<T, Error> {
+        let atom = self.non_finite.apply(atom, offset)?;
+        T::atom(&atom).ok_or(Error::Uncompletable { offset })
+    }
+
+    fn push(&mut self, value: T) {
+        match self.frames.last_mut() {
+            Some(frame) => frame.push(value),
+            None => self.root = Some(value),
+        }
+    }
+
+    /// 对象中缺少值的key，设置了占位值时补上占位值，否则丢弃
+    fn push_placeholder(&mut self, placeholder: Option<&str>) -> Result<(), Error> {
+        match (self.frames.last_mut(), placeholder) {
+            (Some(Frame::Object(_, Some(_))), Some(placeholder)) => {
+                // 占位值总是严格的json，与输入的方言和补全设置无关
+                let value = build(placeholder, Allow::ALL.into())?;
+                self.push(value);
+            }
+            (Some(frame), _) => frame.drop_key(),
+            (None, _) => {}
+        }
+        Ok(())
+    }
+
+    fn finish(mut self, parser: &Parser) -> Result<T, Error> {
+        if let RunState::Error(err) = parser.is_parsed() {
+            return Err(err.clone());
+        }
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+        if self.frames.is_empty() {
+            if let Some(root) = self.root {
+                return Ok(root);
+            }
+            // 根节点是一个原子值
+            return match parser.cut_and_amend(0, true) {
+                Ok(res) => self.atom(res.amend_value(), 0),
+                Err(err) => build_empty(parser.settings(), err),
+            };
+        }
+
+        // 最后一个结构字符之后尚未结束的值，对象中处于key位置的内容会被丢弃，
+        // 除非设置了保留缺少值的key
+        let placeholder = parser.settings().key_placeholder();
+        match self.frames.last_mut() {
+            Some(Frame::Object(_, key @ None)) => {
+                let partial = parser.partial_key(self.last);
+                if let (Some(_), Some(partial)) = (&placeholder, partial) {
+                    *key = unescape_string(&partial);
+                }
+            }
+            _ => {
+                if let Ok(res) = parser.cut_and_amend(self.last, true) {
+                    let value = self.atom(res.amend_value(), self.last)?;
+                    self.push(value);
+                }
+            }
+        }
+        self.push_placeholder(placeholder.as_deref())?;
+
+        // 由内向外闭合，不允许残缺的数组或对象会连同它的key一起被丢弃
+        while let Some(frame) = self.frames.pop() {
+            let allowed = parser.settings().allows_collection(frame.char_type());
+            match self.frames.last_mut() {
+                Some(parent) if allowed => parent.push(frame.into_value()),
+                Some(_) => self.push_placeholder(placeholder.as_deref())?,
+                None if allowed => return Ok(frame.into_value()),
+                None => break,
+            }
+        }
+        // 根节点是不允许残缺的数组或对象
+        let src = parser.src_str();
+        Err(Error::Uncompletable {
+            offset: src.len() - src.trim_start_matches(is_space).len(),
+        })
+    }
+}
+
+impl<T: BuildValue> ScanVisitor for ValueBuilder<T> {
+    fn visit(&mut self, src: &str, idx: usize, char_type: CharType) {
+        if self.error.is_some() {
+            return;
+        }
+        let raw = src[self.last..idx].trim_end_matches(is_space);
+        let atom = raw.trim_start_matches(is_space);
+        let offset = self.last + raw.len() - atom.len();
+        let err = Error::Uncompletable { offset };
+        self.last = idx + 1;
+
+        if char_type == CharType::Colon {
+            match (self.frames.last_mut(), unescape_string(atom)) {
+                (Some(Frame::Object(_, key)), Some(k)) => *key = Some(k),
+                _ => self.error = Some(err),
+            }
+            return;
+        }
+
+        if !atom.is_empty() {
+            match self.atom(atom, offset) {
+                Ok(value) => self.push(value),
+                Err(err) => {
+                    self.error = Some(err);
+                    return;
+                }
+            }
+        }
+        match char_type {
+            CharType::LFB => self.frames.push(Frame::Array(Vec::new())),
+            CharType::LCB => self.frames.push(Frame::Object(T::Object::default(), None)),
+            CharType::RFB | CharType::RCB => {
+                if let Some(frame) = self.frames.pop() {
+                    self.push(frame.into_value());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::loads;
+    use crate::test_utils::{arb_json, normalize};
+    use crate::{Allow, Error, NonFinitePolicy, ParseSettings, Parser};
+    use proptest::prelude::*;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn test_loads() {
+        let cases = [
+            (r#"{"a": [1, 2.5, "x"#, json!({"a": [1, 2.5, "x"]})),
+            (r#"{"a": {"b": tr"#, json!({"a": {"b": true}})),
+            (r#"{"a": 1, "b"#, json!({"a": 1})),
+            (r#"{"a": 1, "b":"#, json!({"a": 1})),
+            (r#"[NaN, -Inf"#, json!([null, null])),
+            (r#""abc\"#, json!("abc")),
+            ("[[1], [2", json!([[1], [2]])),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(loads(input, Allow::ALL).unwrap(), expected);
+        }
+        assert_eq!(loads("[1, 2", Allow::ARR).unwrap(), json!([1]));
+        assert_eq!(
+            loads(r#"{"a": [1, 2], "b": [3"#, Allow::OBJ | Allow::NUM).unwrap(),
+            json!({"a": [1, 2]})
+        );
+        assert!(loads("[1, 2", Allow::OBJ).is_err());
+        assert!(loads("", Allow::ALL).is_err());
+    }
+
+    #[test]
+    fn test_loads_non_finite() {
+        let settings = ParseSettings::new().allow(Allow::ALL);
+        let src = r#"{"a": NaN, "b": [Infinity, -Inf"#;
+        let res = loads(src, settings.clone().non_finite(NonFinitePolicy::String)).unwrap();
+        assert_eq!(res, json!({"a": "NaN", "b": ["Infinity", "-Infinity"]}));
+        let res = loads(src, settings.clone().non_finite(NonFinitePolicy::Null)).unwrap();
+        assert_eq!(res, json!({"a": null, "b": [null, null]}));
+        assert_eq!(
+            loads(src, settings.clone().non_finite(NonFinitePolicy::Error)),
+            Err(Error::NonFinite { offset: 6 })
+        );
+        assert_eq!(
+            loads("-Infin", settings.non_finite(NonFinitePolicy::Error)),
+            Err(Error::NonFinite { offset: 0 })
+        );
+    }
+
+    #[cfg(feature = "preserve_order")]
+    #[test]
+    fn test_loads_preserve_order() {
+        let value = loads(r#"{"b": 1, "a": 2, "c": 3"#, Allow::ALL).unwrap();
+        let keys: Vec<&String> = value.as_object().unwrap().keys().collect();
+        assert_eq!(keys, ["b", "a", "c"]);
+    }
+
+    #[cfg(feature = "arbitrary_precision")]
+    #[test]
+    fn test_loads_arbitrary_precision() {
+        let src = r#"{"id": 100000000000000000000000001, "price": [0.1000000000000000000001"#;
+        let value = loads(src, Allow::ALL).unwrap();
+        assert_eq!(value["id"].to_string(), "100000000000000000000000001");
+        assert_eq!(value["price"][0].to_string(), "0.1000000000000000000001");
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(256))]
+        #[test]
+        fn loads_prefix_prop(s in arb_json()) {
+            let s = s.to_string();
+            for (i, _) in s.char_indices().skip(1) {
+                if Parser::parser_with(&s[..i], Allow::ALL).is_ok() {
+                    assert!(loads(&s[..i], Allow::ALL).is_ok(), "input: {:?}", &s[..i]);
+                }
+            }
+            let expected = json5::from_str::<Value>(&s).unwrap();
+            assert_eq!(normalize(loads(&s, Allow::ALL).unwrap()), normalize(expected));
+        }
+    }
+}
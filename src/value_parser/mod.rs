@@ -4,7 +4,7 @@ mod parse_string;
 
 pub use parse_num::parse_num;
 pub use parse_spec::{parse_bool, parse_infinity, parse_nan, parse_ninfinity, parse_null};
-pub use parse_string::{parse_string, sp};
+pub use parse_string::{is_space, parse_string, sp, unescape_string};
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct VParserRes {
@@ -23,4 +23,12 @@ impl VParserRes {
     pub fn is_complete(&self) -> bool {
         self.is_complete
     }
+
+    pub fn amend_value(&self) -> &str {
+        &self.amend_value
+    }
+
+    pub fn into_amend_value(self) -> String {
+        self.amend_value
+    }
 }
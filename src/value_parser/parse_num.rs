@@ -7,6 +7,7 @@ use nom::{
 };
 
 use super::VParserRes;
+use crate::Error;
 
 // 解析可能的科学计数尾部
 fn parse_e_(input: &str) -> IResult<&str, &str> {
@@ -25,7 +26,7 @@ fn parse_base_(input: &str) -> IResult<&str, &str> {
     )))(input)
 }
 
-pub fn parse_num(i: &str) -> Result<VParserRes, ()> {
+pub fn parse_num(i: &str) -> Result<VParserRes, Error> {
     // 尝试解析基数部分
     let base_res = parse_base_(i);
     if let Ok((remaining, matched)) = base_res {
@@ -46,12 +47,14 @@ pub fn parse_num(i: &str) -> Result<VParserRes, ()> {
             return Ok(VParserRes::new(matched.to_string() + exponent_res.1, false));
         } else {
             // 存在未完全解析的指数部分
-            return Err(());
+            return Err(Error::Uncompletable {
+                offset: matched.len(),
+            });
         }
     }
 
     // 基数部分解析失败
-    Err(())
+    Err(Error::Uncompletable { offset: 0 })
 }
 
 #[cfg(test)]
@@ -102,6 +105,7 @@ mod test_num {
         assert_eq!(parse_num("0."), Ok(VParserRes::new("0", false)));
         assert_eq!(parse_num("12."), Ok(VParserRes::new("12", false)));
         assert!(parse_num("2E-").is_err());
+        assert_eq!(parse_num("-123E"), Err(Error::Uncompletable { offset: 4 }));
     }
 
     #[test]
@@ -5,10 +5,10 @@ use nom::IResult;
 use crate::utils;
 
 use super::VParserRes;
-
+use crate::Error;
 
 #[allow(unused)]
-pub fn parse_spec(i: &str) -> Result<VParserRes, ()> {
+pub fn parse_spec(i: &str) -> Result<VParserRes, Error> {
     let res: IResult<&str, &str> = alt((
         tag("false"),
         tag("true"),
@@ -34,7 +34,7 @@ pub fn shared(
     spec_vec: &[(&str, usize)],
     res: IResult<&str, &str>,
     i: &str,
-) -> Result<VParserRes, ()> {
+) -> Result<VParserRes, Error> {
     let completion = spec_vec.iter().find_map(|(pattern, min_len)| {
         if utils::is_prefix_with_min_length(pattern, i, *min_len) {
             utils::complement_after(pattern, i)
@@ -49,42 +49,42 @@ pub fn shared(
         if completion.is_none() {
             Ok(VParserRes::new(res.1, true))
         } else {
-            Err(())
+            Err(Error::Uncompletable { offset: 0 })
         }
     } else {
-        Err(())
+        Err(Error::Uncompletable { offset: 0 })
     }
 }
 
-pub fn parse_bool(i: &str) -> Result<VParserRes, ()> {
+pub fn parse_bool(i: &str) -> Result<VParserRes, Error> {
     let res: IResult<&str, &str> = alt((tag("false"), tag("true")))(i);
     let spec_vec = [("true", 1), ("false", 1)];
 
     shared(&spec_vec, res, i)
 }
 
-pub fn parse_nan(i: &str) -> Result<VParserRes, ()> {
+pub fn parse_nan(i: &str) -> Result<VParserRes, Error> {
     let res: IResult<&str, &str> = tag("NaN")(i);
     let spec_vec = [("NaN", 1)];
 
     shared(&spec_vec, res, i)
 }
 
-pub fn parse_null(i: &str) -> Result<VParserRes, ()> {
+pub fn parse_null(i: &str) -> Result<VParserRes, Error> {
     let res: IResult<&str, &str> = tag("null")(i);
     let spec_vec = [("null", 1)];
 
     shared(&spec_vec, res, i)
 }
 
-pub fn parse_infinity(i: &str) -> Result<VParserRes, ()> {
+pub fn parse_infinity(i: &str) -> Result<VParserRes, Error> {
     let res: IResult<&str, &str> = tag("Infinity")(i);
     let spec_vec = [("Infinity", 1)];
 
     shared(&spec_vec, res, i)
 }
 
-pub fn parse_ninfinity(i: &str) -> Result<VParserRes, ()> {
+pub fn parse_ninfinity(i: &str) -> Result<VParserRes, Error> {
     let res: IResult<&str, &str> = tag("-Infinity")(i);
     let spec_vec = [("-Infinity", 2)];
 
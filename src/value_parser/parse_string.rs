@@ -1,8 +1,9 @@
 use super::VParserRes;
 use crate::parser::{CharType, EscapeCnt};
+use crate::Error;
 use nom::{bytes::complete::take_while, IResult};
 
-fn is_space(c: char) -> bool {
+pub fn is_space(c: char) -> bool {
     c == ' ' || c == '\t' || c == '\r' || c == '\n'
 }
 
@@ -10,11 +11,11 @@ pub fn sp(i: &str) -> IResult<&str, &str> {
     take_while(is_space)(i)
 }
 
-
-pub fn parse_string(i: &str) -> Result<VParserRes, ()> {
-    let (s, _) = sp(i).map_err(|_| ())?;
+pub fn parse_string(i: &str) -> Result<VParserRes, Error> {
+    let (s, _) = sp(i).map_err(|_| Error::Uncompletable { offset: 0 })?;
+    let start = i.len() - s.len();
     if s.is_empty() {
-        return Err(());
+        return Err(Error::Uncompletable { offset: start });
     }
 
     let mut first = true;
@@ -26,7 +27,7 @@ pub fn parse_string(i: &str) -> Result<VParserRes, ()> {
 
     for (idx, c) in s.char_indices() {
         if first && c != '"' {
-            return Err(());
+            return Err(Error::Uncompletable { offset: start });
         } else if first && c == '"' {
             first = false;
             continue;
@@ -38,13 +39,17 @@ pub fn parse_string(i: &str) -> Result<VParserRes, ()> {
             break;
         } else if char_type == CharType::Escape {
             last_esc = Some(idx);
+        } else if char_type == CharType::InvalidEscape {
+            return Err(Error::InvalidEscape {
+                offset: start + last_esc.unwrap_or(idx),
+            });
         }
     }
 
     if need_cmpl {
-        if esc_cnt.cnt() == 1 {
+        if let (1, Some(last_esc)) = (esc_cnt.cnt(), last_esc) {
             // 这时候需要从最后一个转义符号恢复
-            amend_value.push_str(&s[..last_esc.expect("Should have value")]);
+            amend_value.push_str(&s[..last_esc]);
         } else {
             amend_value.push_str(s);
         }
@@ -56,10 +61,66 @@ pub fn parse_string(i: &str) -> Result<VParserRes, ()> {
     Ok(VParserRes::new(amend_value, !need_cmpl))
 }
 
+/// 将一个完整的、带引号的json字符串还原为其表示的内容，
+/// 与`serde_json`不同，这里允许字符串中出现未转义的控制字符
+pub fn unescape_string(s: &str) -> Option<String> {
+    let inner = s.strip_prefix('"')?.strip_suffix('"')?;
+    let mut res = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            res.push(c);
+            continue;
+        }
+        let c = match chars.next()? {
+            '"' => '"',
+            '\\' => '\\',
+            '/' => '/',
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'u' => {
+                let hi = read_hex4(&mut chars)?;
+                if (0xD800..0xDC00).contains(&hi) {
+                    // 代理对，需要再读取一个\uXXXX
+                    let mut lookahead = chars.clone();
+                    let lo = match (lookahead.next(), lookahead.next()) {
+                        (Some('\\'), Some('u')) => read_hex4(&mut lookahead),
+                        _ => None,
+                    };
+                    match lo {
+                        Some(lo) if (0xDC00..0xE000).contains(&lo) => {
+                            chars = lookahead;
+                            let code = 0x10000 + ((hi - 0xD800) << 10) + (lo - 0xDC00);
+                            char::from_u32(code)?
+                        }
+                        _ => char::REPLACEMENT_CHARACTER,
+                    }
+                } else {
+                    char::from_u32(hi).unwrap_or(char::REPLACEMENT_CHARACTER)
+                }
+            }
+            _ => return None,
+        };
+        res.push(c);
+    }
+    Some(res)
+}
+
+fn read_hex4(chars: &mut std::str::Chars) -> Option<u32> {
+    let mut code = 0;
+    for _ in 0..4 {
+        code = code * 16 + chars.next()?.to_digit(16)?;
+    }
+    Some(code)
+}
+
 #[cfg(test)]
 mod test {
 
-    use crate::value_parser::parse_string::parse_string;
+    use crate::value_parser::parse_string::{parse_string, unescape_string};
     use proptest::prelude::*;
 
     proptest! {
@@ -79,6 +140,36 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_unescape() {
+        let test_vec = [
+            r#""abc""#,
+            r#""a\"b\\c\/""#,
+            r#""\b\f\n\r\t""#,
+            r#""\u00e9\u4e16""#,
+            r#""\ud83d\ude00""#,
+        ];
+        for i in test_vec {
+            assert_eq!(
+                unescape_string(i),
+                Some(serde_json::from_str::<String>(i).unwrap())
+            );
+        }
+        assert_eq!(unescape_string("\"a\tb\""), Some("a\tb".to_string()));
+        assert_eq!(unescape_string(r#""\ud83d""#), Some("\u{fffd}".to_string()));
+        assert_eq!(unescape_string(r#""\x41""#), None);
+        assert_eq!(unescape_string(r#""abc"#), None);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(1000))]
+        #[test]
+        fn test_unescape_prop(test_s in ".*") {
+            let s = serde_json::to_string(&test_s).unwrap();
+            assert_eq!(unescape_string(&s), Some(test_s));
+        }
+    }
+
     #[test]
     fn test_cases() {
         let test_vec = vec![